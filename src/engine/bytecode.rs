@@ -0,0 +1,525 @@
+use std::fmt::Display;
+
+use super::types::{Cursor, DataTypes};
+
+/// Every Nebulang ByteCode file starts with these bytes
+pub const MAGIC: [u8; 4] = *b"NBLC";
+/// Bumped every time the layout of the file or the opcodes change
+pub const VERSION: u16 = 9;
+/// Written in place of the entry function when the program has no #main
+pub const NO_ENTRY: u32 = u32::MAX;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum OpCodes {
+    // Literals
+    PushInt(i64),
+    PushFlt(f64),
+    PushBol(bool),
+    /// Index into the program string table
+    PushStr(u32),
+    PushVoid,
+    PushChr(char),
+    // Variables, the argument is the local slot in the current frame
+    Load(u32),
+    Store(u32),
+    Pop,
+    // Operators, they pop the right side first and then the left side
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Lt,
+    Gt,
+    Equ,
+    NotEqu,
+    EquGt,
+    EquLt,
+    BitOr,
+    BitAnd,
+    BitLeft,
+    BitRight,
+    /// Negates the number on top of the stack
+    Neg,
+    // Control flow, the argument is the index of the instruction to go to
+    Jump(u32),
+    /// Pops a bol and jumps if it is false
+    JumpIfFalse(u32),
+    // Calls
+    /// Function index in the program and the amount of arguments
+    Call(u32, u8),
+    /// Name of the native function in the string table and the arguments
+    CallNative(u32, u8),
+    /// Name of the method in the string table, the function called when the
+    /// kind of the receiver does not replace it and the arguments, the
+    /// receiver first
+    CallMethod(u32, u32, u8),
+    Return,
+    // Structs
+    /// Pops the amount of fields given, the first field was pushed first
+    MakeStruct(u32),
+    /// Replaces the struct on top of the stack with one of its fields
+    GetField(u32),
+    /// Pops a value and the struct under it and pushes the struct with the
+    /// value as the field given
+    SetField(u32),
+    /// Marks the struct on top of the stack as made by the kind given, see
+    /// `Program::kinds`
+    SetKind(u32),
+    // Vecs
+    /// Pops the amount of items given, the first item was pushed first
+    MakeVec(u32),
+    /// Pops the index and the vec and pushes the item at the index
+    GetIndex,
+    // Maps
+    /// Pops the amount of key and value pairs given, each key was pushed
+    /// right before its value
+    MakeMap(u32),
+    /// Replaces the map on top of the stack with the value of the key in the
+    /// string table
+    GetKey(u32),
+    /// Converts the value on top of the stack to the type given
+    Cast(Casts),
+}
+
+/// Types a value can be converted to in the runtime
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Casts {
+    Int,
+    Flt,
+    Bol,
+    Chr,
+    Str,
+}
+
+impl Casts {
+    /// Only basic types can be converted, casting to any other type only
+    /// tells the checker what the value is
+    pub fn new(data_type: &DataTypes) -> Option<Self> {
+        match data_type {
+            DataTypes::Int => Some(Self::Int),
+            DataTypes::Flt => Some(Self::Flt),
+            DataTypes::Bol => Some(Self::Bol),
+            DataTypes::Chr => Some(Self::Chr),
+            DataTypes::Str => Some(Self::Str),
+            _ => None,
+        }
+    }
+
+    fn code(self: &Self) -> u8 {
+        match self {
+            Self::Int => 0x01,
+            Self::Flt => 0x02,
+            Self::Bol => 0x03,
+            Self::Chr => 0x04,
+            Self::Str => 0x05,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x01 => Some(Self::Int),
+            0x02 => Some(Self::Flt),
+            0x03 => Some(Self::Bol),
+            0x04 => Some(Self::Chr),
+            0x05 => Some(Self::Str),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Casts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int => write!(f, "int"),
+            Self::Flt => write!(f, "flt"),
+            Self::Bol => write!(f, "bol"),
+            Self::Chr => write!(f, "chr"),
+            Self::Str => write!(f, "str"),
+        }
+    }
+}
+
+impl OpCodes {
+    /// Operator written in the source for the opcodes of operators
+    pub fn symbol(self: &Self) -> Option<&'static str> {
+        Some(match self {
+            Self::Add       => "+",
+            Self::Sub       => "-",
+            Self::Mul       => "*",
+            Self::Div       => "/",
+            Self::Mod       => "%",
+            Self::Lt        => "<",
+            Self::Gt        => ">",
+            Self::Equ       => "==",
+            Self::NotEqu    => "!=",
+            Self::EquGt     => ">=",
+            Self::EquLt     => "<=",
+            Self::BitOr     => "|",
+            Self::BitAnd    => "&",
+            Self::BitLeft   => "<<",
+            Self::BitRight  => ">>",
+            _ => return None,
+        })
+    }
+
+    pub fn code(self: &Self) -> u8 {
+        match self {
+            Self::PushInt(_)        => 0x01,
+            Self::PushFlt(_)        => 0x02,
+            Self::PushBol(_)        => 0x03,
+            Self::PushStr(_)        => 0x04,
+            Self::PushVoid          => 0x05,
+            Self::PushChr(_)        => 0x06,
+            Self::Load(_)           => 0x10,
+            Self::Store(_)          => 0x11,
+            Self::Pop               => 0x12,
+            Self::Add               => 0x20,
+            Self::Sub               => 0x21,
+            Self::Mul               => 0x22,
+            Self::Div               => 0x23,
+            Self::Mod               => 0x24,
+            Self::Lt                => 0x25,
+            Self::Gt                => 0x26,
+            Self::Equ               => 0x27,
+            Self::EquGt             => 0x28,
+            Self::EquLt             => 0x29,
+            Self::BitOr             => 0x2A,
+            Self::BitAnd            => 0x2B,
+            Self::BitLeft           => 0x2C,
+            Self::BitRight          => 0x2D,
+            Self::NotEqu            => 0x2E,
+            Self::Neg               => 0x2F,
+            Self::Jump(_)           => 0x40,
+            Self::JumpIfFalse(_)    => 0x41,
+            Self::Call(_, _)        => 0x30,
+            Self::CallNative(_, _)  => 0x31,
+            Self::Return            => 0x32,
+            Self::CallMethod(_, _, _) => 0x33,
+            Self::MakeStruct(_)     => 0x50,
+            Self::GetField(_)       => 0x51,
+            Self::MakeVec(_)        => 0x52,
+            Self::GetIndex          => 0x53,
+            Self::MakeMap(_)        => 0x54,
+            Self::GetKey(_)         => 0x55,
+            Self::Cast(_)           => 0x56,
+            Self::SetField(_)       => 0x57,
+            Self::SetKind(_)        => 0x58,
+        }
+    }
+
+    fn write(self: &Self, buf: &mut Vec<u8>) {
+        buf.push(self.code());
+        match self {
+            Self::PushInt(int) => buf.extend_from_slice(&int.to_le_bytes()),
+            Self::PushFlt(flt) => buf.extend_from_slice(&flt.to_le_bytes()),
+            Self::PushBol(bol) => buf.push(*bol as u8),
+            Self::PushChr(chr) => write_u32(buf, *chr as u32),
+            Self::PushStr(index)
+            | Self::Load(index)
+            | Self::Store(index)
+            | Self::Jump(index)
+            | Self::JumpIfFalse(index)
+            | Self::MakeStruct(index)
+            | Self::GetField(index)
+            | Self::SetField(index)
+            | Self::SetKind(index)
+            | Self::MakeVec(index)
+            | Self::MakeMap(index)
+            | Self::GetKey(index) => write_u32(buf, *index),
+            Self::Call(index, args)
+            | Self::CallNative(index, args) => {
+                write_u32(buf, *index);
+                buf.push(*args);
+            }
+            Self::CallMethod(method, function, args) => {
+                write_u32(buf, *method);
+                write_u32(buf, *function);
+                buf.push(*args);
+            }
+            Self::Cast(cast) => buf.push(cast.code()),
+            _ => {}
+        }
+    }
+
+    fn read(reader: &mut ByteReader) -> Result<Self, ByteCodeError> {
+        let code = reader.u8()?;
+        Ok(match code {
+            0x01 => Self::PushInt(i64::from_le_bytes(reader.array()?)),
+            0x02 => Self::PushFlt(f64::from_le_bytes(reader.array()?)),
+            0x03 => Self::PushBol(reader.u8()? != 0),
+            0x04 => Self::PushStr(reader.u32()?),
+            0x05 => Self::PushVoid,
+            0x06 => {
+                let code = reader.u32()?;
+                Self::PushChr(char::from_u32(code).ok_or(ByteCodeError::InvalidChar(code))?)
+            }
+            0x10 => Self::Load(reader.u32()?),
+            0x11 => Self::Store(reader.u32()?),
+            0x12 => Self::Pop,
+            0x20 => Self::Add,
+            0x21 => Self::Sub,
+            0x22 => Self::Mul,
+            0x23 => Self::Div,
+            0x24 => Self::Mod,
+            0x25 => Self::Lt,
+            0x26 => Self::Gt,
+            0x27 => Self::Equ,
+            0x28 => Self::EquGt,
+            0x29 => Self::EquLt,
+            0x2A => Self::BitOr,
+            0x2B => Self::BitAnd,
+            0x2C => Self::BitLeft,
+            0x2D => Self::BitRight,
+            0x2E => Self::NotEqu,
+            0x2F => Self::Neg,
+            0x40 => Self::Jump(reader.u32()?),
+            0x41 => Self::JumpIfFalse(reader.u32()?),
+            0x30 => Self::Call(reader.u32()?, reader.u8()?),
+            0x31 => Self::CallNative(reader.u32()?, reader.u8()?),
+            0x32 => Self::Return,
+            0x33 => Self::CallMethod(reader.u32()?, reader.u32()?, reader.u8()?),
+            0x50 => Self::MakeStruct(reader.u32()?),
+            0x51 => Self::GetField(reader.u32()?),
+            0x52 => Self::MakeVec(reader.u32()?),
+            0x53 => Self::GetIndex,
+            0x54 => Self::MakeMap(reader.u32()?),
+            0x55 => Self::GetKey(reader.u32()?),
+            0x56 => {
+                let code = reader.u8()?;
+                Self::Cast(Casts::from_code(code).ok_or(ByteCodeError::UnknownCast(code))?)
+            }
+            0x57 => Self::SetField(reader.u32()?),
+            0x58 => Self::SetKind(reader.u32()?),
+            _ => return Err(ByteCodeError::UnknownOpCode(code)),
+        })
+    }
+}
+
+impl Display for OpCodes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A compiled Nebulang function
+#[derive(Debug, Default, Clone)]
+pub struct Function {
+    pub name: String,
+    pub params: u8,
+    /// Amount of local slots, parameters included
+    pub locals: u32,
+    pub code: Vec<OpCodes>,
+    /// Source position of each instruction in `code`
+    pub cursors: Vec<Cursor>,
+}
+
+impl Function {
+    pub fn new(name: String, params: u8) -> Self {
+        Self {
+            name,
+            params,
+            ..Default::default()
+        }
+    }
+    pub fn emit(self: &mut Self, op: OpCodes, cursor: Cursor) -> usize {
+        self.code.push(op);
+        self.cursors.push(cursor);
+        return self.code.len() - 1;
+    }
+}
+
+/// A struct that extends another one, its values know it so the functions
+/// replacing the ones of the extended struct are called on them
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Kind {
+    pub name: String,
+    /// Name of each method in the string table and the function it calls
+    pub methods: Vec<(u32, u32)>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Program {
+    pub strings: Vec<String>,
+    pub functions: Vec<Function>,
+    pub kinds: Vec<Kind>,
+    pub entry: Option<u32>,
+}
+
+impl Program {
+    /// Returns the index of the string in the string table, adding it if it
+    /// was not there yet
+    pub fn intern(self: &mut Self, string: &str) -> u32 {
+        if let Some(index) = self.strings.iter().position(|s| s == string) {
+            return index as u32;
+        }
+        self.strings.push(string.to_string());
+        return (self.strings.len() - 1) as u32;
+    }
+
+    pub fn function_index(self: &Self, name: &str) -> Option<u32> {
+        self.functions
+            .iter()
+            .position(|f| f.name == name)
+            .map(|index| index as u32)
+    }
+
+    pub fn kind_index(self: &Self, name: &str) -> Option<u32> {
+        self.kinds
+            .iter()
+            .position(|kind| kind.name == name)
+            .map(|index| index as u32)
+    }
+
+    /// Layout:
+    /// magic, version, string table, functions, kinds, entry function index
+    pub fn to_bytes(self: &Self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MAGIC);
+        buf.extend_from_slice(&VERSION.to_le_bytes());
+        write_u32(&mut buf, self.strings.len() as u32);
+        for string in &self.strings {
+            write_str(&mut buf, string);
+        }
+        write_u32(&mut buf, self.functions.len() as u32);
+        for function in &self.functions {
+            write_str(&mut buf, &function.name);
+            buf.push(function.params);
+            write_u32(&mut buf, function.locals);
+            write_u32(&mut buf, function.code.len() as u32);
+            for op in &function.code {
+                op.write(&mut buf);
+            }
+            // Line table, one entry per instruction
+            for cursor in &function.cursors {
+                write_u32(&mut buf, cursor.line as u32);
+                write_u32(&mut buf, cursor.column as u32);
+            }
+        }
+        write_u32(&mut buf, self.kinds.len() as u32);
+        for kind in &self.kinds {
+            write_str(&mut buf, &kind.name);
+            write_u32(&mut buf, kind.methods.len() as u32);
+            for (method, function) in &kind.methods {
+                write_u32(&mut buf, *method);
+                write_u32(&mut buf, *function);
+            }
+        }
+        write_u32(&mut buf, self.entry.unwrap_or(NO_ENTRY));
+        return buf;
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ByteCodeError {
+    Magic,
+    Version(u16),
+    UnexpectedEnd,
+    UnknownOpCode(u8),
+    UnknownCast(u8),
+    InvalidChar(u32),
+    InvalidString,
+}
+
+impl Display for ByteCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Magic => write!(f, "Not a Nebulang ByteCode file"),
+            Self::Version(version) => write!(
+                f, "ByteCode version {} is not supported, expected {}",
+                version, VERSION
+            ),
+            Self::UnexpectedEnd => write!(f, "ByteCode ended unexpectedly"),
+            Self::UnknownOpCode(code) => write!(f, "Unknown opcode 0x{:02X}", code),
+            Self::UnknownCast(code) => write!(f, "Unknown cast 0x{:02X}", code),
+            Self::InvalidChar(code) => write!(f, "0x{:X} is not a valid chr", code),
+            Self::InvalidString => write!(f, "String table is not valid UTF-8"),
+        }
+    }
+}
+
+impl Program {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ByteCodeError> {
+        let mut reader = ByteReader { bytes, pos: 0 };
+        if reader.array::<4>()? != MAGIC {
+            return Err(ByteCodeError::Magic);
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version != VERSION {
+            return Err(ByteCodeError::Version(version));
+        }
+        let mut program = Program::default();
+        for _ in 0..reader.u32()? {
+            program.strings.push(reader.string()?);
+        }
+        for _ in 0..reader.u32()? {
+            let mut function = Function::new(reader.string()?, reader.u8()?);
+            function.locals = reader.u32()?;
+            let len = reader.u32()?;
+            for _ in 0..len {
+                function.code.push(OpCodes::read(&mut reader)?);
+            }
+            for _ in 0..len {
+                let line = reader.u32()? as u64;
+                let column = reader.u32()? as u64;
+                function.cursors.push(Cursor { pos: 0, line, column });
+            }
+            program.functions.push(function);
+        }
+        for _ in 0..reader.u32()? {
+            let mut kind = Kind {
+                name: reader.string()?,
+                methods: vec![],
+            };
+            for _ in 0..reader.u32()? {
+                kind.methods.push((reader.u32()?, reader.u32()?));
+            }
+            program.kinds.push(kind);
+        }
+        program.entry = match reader.u32()? {
+            NO_ENTRY => None,
+            entry => Some(entry),
+        };
+        Ok(program)
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn array<const N: usize>(self: &mut Self) -> Result<[u8; N], ByteCodeError> {
+        let slice = self.bytes
+            .get(self.pos..self.pos + N)
+            .ok_or(ByteCodeError::UnexpectedEnd)?;
+        self.pos += N;
+        let mut array = [0; N];
+        array.copy_from_slice(slice);
+        Ok(array)
+    }
+    fn u8(self: &mut Self) -> Result<u8, ByteCodeError> {
+        Ok(self.array::<1>()?[0])
+    }
+    fn u32(self: &mut Self) -> Result<u32, ByteCodeError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+    fn string(self: &mut Self) -> Result<String, ByteCodeError> {
+        let len = self.u32()? as usize;
+        let slice = self.bytes
+            .get(self.pos..self.pos + len)
+            .ok_or(ByteCodeError::UnexpectedEnd)?;
+        self.pos += len;
+        String::from_utf8(slice.to_vec()).map_err(|_| ByteCodeError::InvalidString)
+    }
+}
+
+fn write_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_str(buf: &mut Vec<u8>, string: &str) {
+    write_u32(buf, string.len() as u32);
+    buf.extend_from_slice(string.as_bytes());
+}
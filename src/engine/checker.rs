@@ -0,0 +1,2273 @@
+use hashbrown::{HashMap, HashSet};
+
+use super::core::{format_pieces, Fields, Native, Natives, Structs};
+use super::diagnostic::Diagnostic;
+use super::types::{
+    method_name, DataTypes, Node, Operators, Scope, TokenTypes, TypeSignature
+};
+
+/// What the checker found out about the program, for the passes after it
+#[derive(Debug, Default, Clone)]
+pub struct Analysis {
+    /// Type of every expression and declared variable, by node id
+    pub types: HashMap<u64, TypeSignature>,
+    /// Node that declared the variable of every use of one, by node id
+    pub bindings: HashMap<u64, u64>,
+    /// Position in their struct of the fields that are accessed or set in a
+    /// struct literal, by node id
+    pub fields: HashMap<u64, u32>,
+    /// Function called by every method call, index and call to a function
+    /// inherited from an extended struct or imported from another file, by
+    /// node id
+    pub methods: HashMap<u64, String>,
+    /// Int values used where a flt is expected, they are turned into flts,
+    /// by node id
+    pub widened: HashSet<u64>,
+    /// Values of structs extending `vec` used as their vec, with the
+    /// position of the field their items are in, by node id
+    pub items: HashMap<u64, u32>,
+    /// Calls of the functions of `vec` on a struct extending it that give
+    /// back the struct with the new items, with the position of the field
+    /// they go in, by node id
+    pub rewrapped: HashMap<u64, u32>,
+    /// Method calls whose function is replaced in a struct extending the
+    /// one of the receiver, the kind of the receiver picks it when running,
+    /// by node id
+    pub dispatched: HashSet<u64>,
+    /// Structs of the file that extend another struct, with the function
+    /// that each of their methods calls
+    pub kinds: Vec<(String, Vec<(String, String)>)>,
+    /// Kind of the struct literals of structs that extend another struct,
+    /// by node id
+    pub made: HashMap<u64, String>,
+    /// Parameters of the functions declared in the file, the values the
+    /// host calls them with are checked against them
+    pub params: HashMap<String, Params>,
+    /// Fields of the structs the file can use, the ones it imports and the
+    /// ones of the host included
+    pub structs: Structs,
+    /// What other files can import from this one
+    pub exports: Exports,
+}
+
+/// Parameters of a function of the file as the host sees them
+#[derive(Debug, Default, Clone)]
+pub struct Params {
+    /// Type parameters of the function and of its struct, `t` in `vec<t>`
+    pub generics: Vec<String>,
+    pub types: Vec<TypeSignature>,
+}
+
+/// `map<k><v>`, it lives in the runtime and is imported from std
+pub const MAP: &str = "map";
+
+/// Where the structs of the program embedding the language come from
+pub const HOST: &str = "host";
+
+/// Functions and structs a file exports, by the name they are imported with
+#[derive(Debug, Default, Clone)]
+pub struct Exports {
+    functions: HashMap<String, Signature>,
+    structs: HashMap<String, StructSignature>,
+    /// Types of the runtime imported from std
+    builtins: Vec<String>,
+}
+
+impl Exports {
+    /// Adds the type of the std library to `imports`, `false` when std does
+    /// not have it
+    pub fn import_std(name: &str, imports: &mut Exports) -> bool {
+        if name != MAP {
+            return false;
+        }
+        if !imports.builtins.iter().any(|builtin| builtin == name) {
+            imports.builtins.push(name.to_string());
+        }
+        return true;
+    }
+
+    /// Adds a struct of the program embedding the language, every file can
+    /// use it without importing it. `false` when the name is taken.
+    pub fn host_struct(self: &mut Self, name: &str, fields: Vec<(String, TypeSignature)>) -> bool {
+        if self.structs.contains_key(name) || name == MAP {
+            return false;
+        }
+        self.structs.insert(name.to_string(), StructSignature {
+            node: 0,
+            import: Some(HOST.to_string()),
+            generics: vec![],
+            fields,
+            extends: None,
+        });
+        return true;
+    }
+
+    /// Adds the exported function to `imports`, it is called with the name
+    /// it was compiled with in `module`. `false` when it is not exported.
+    pub fn import_function(self: &Self, name: &str, module: &str, imports: &mut Exports) -> bool {
+        let mut signature = match self.functions.get(name) {
+            Some(signature) => signature.clone(),
+            None => return false,
+        };
+        signature.import = Some(format!("{}@{}", name, module));
+        imports.functions.insert(name.to_string(), signature);
+        return true;
+    }
+
+    /// Adds the exported struct and its functions to `imports`
+    pub fn import_struct(self: &Self, name: &str, module: &str, imports: &mut Exports) -> bool {
+        let mut structure = match self.structs.get(name) {
+            Some(structure) => structure.clone(),
+            None => return false,
+        };
+        structure.import = Some(module.to_string());
+        imports.structs.insert(name.to_string(), structure);
+        let functions = self.functions
+            .keys()
+            .filter(|function| function.split_once('^').is_some_and(|(_, s)| s == name));
+        for function in functions {
+            self.import_function(function, module, imports);
+        }
+        return true;
+    }
+}
+
+/// Declarations at the top of the file, the exported ones included
+pub fn declarations(nodes: &[Node]) -> Vec<&Node> {
+    let root = match nodes.first() {
+        Some(root) => root,
+        None => return vec![],
+    };
+    root.children
+        .iter()
+        .filter_map(|id| nodes.get(*id as usize))
+        .flat_map(|node| match node.token {
+            TokenTypes::Export => node.children
+                .iter()
+                .filter_map(|id| nodes.get(*id as usize))
+                .collect(),
+            _ => vec![node],
+        })
+        .collect()
+}
+
+/// Path of the file the declaration is imported from, `dep.nl` in
+/// `#multiply@dep.nl`
+pub fn import_path(nodes: &[Node], declaration: &Node) -> Option<String> {
+    let import = declaration.children
+        .iter()
+        .filter_map(|id| nodes.get(*id as usize))
+        .find(|child| child.token == TokenTypes::Import)?;
+    Some(import.children
+        .iter()
+        .filter_map(|id| nodes.get(*id as usize))
+        .filter_map(|piece| match &piece.token {
+            TokenTypes::Path(piece) => Some(piece.as_str()),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Name of the function declared by the node, functions of a struct are
+/// named after it, see `method_name`
+pub fn function_name(nodes: &[Node], function: &Node) -> Option<String> {
+    let name = match &function.token {
+        TokenTypes::Function(name) => name,
+        _ => return None,
+    };
+    let structure = function.children
+        .iter()
+        .filter_map(|id| nodes.get(*id as usize))
+        .filter(|child| child.token == TokenTypes::Implement)
+        .flat_map(|implement| implement.children.iter())
+        .find_map(|id| match nodes.get(*id as usize).map(|node| &node.token) {
+            Some(TokenTypes::Type(DataTypes::User(structure))) => Some(structure.clone()),
+            _ => None,
+        });
+    match structure {
+        Some(structure) => Some(method_name(name, &structure)),
+        None => Some(name.clone()),
+    }
+}
+
+/// File of a name compiled in another file, `dep.nl` in `multiply@dep.nl`
+fn module_of(compiled: &str) -> &str {
+    compiled.split_once('@').map(|(_, module)| module).unwrap_or(compiled)
+}
+
+/// Functions of structs as they are called, `new^person` is `person:new`
+fn readable_name(name: &str) -> String {
+    match name.split_once('^') {
+        Some((function, structure)) => format!("{}:{}", structure, function),
+        None => name.to_string(),
+    }
+}
+
+/// Parameters and return type of a function declared in the program
+#[derive(Debug, Clone)]
+pub struct Signature {
+    node: u64,
+    /// Name it was compiled with when it was imported, `multiply@dep.nl`
+    import: Option<String>,
+    /// Methods take the struct they are called on as their first parameter,
+    /// `self self`
+    receiver: bool,
+    /// Type parameters of the function and of its struct
+    generics: Vec<String>,
+    /// Struct of the function as it is written with it, `counter<u>` in
+    /// `#get^counter<u>`
+    owner: Option<TypeSignature>,
+    params: Vec<TypeSignature>,
+    returns: TypeSignature,
+}
+
+/// Fields of a struct declared in the program, in the order they were
+/// declared
+#[derive(Debug, Clone)]
+pub struct StructSignature {
+    node: u64,
+    /// File it was imported from
+    import: Option<String>,
+    generics: Vec<String>,
+    fields: Vec<(String, TypeSignature)>,
+    /// Struct it got its functions from, `^counter^limited;`, in terms of
+    /// its own type parameters
+    extends: Option<TypeSignature>,
+}
+
+impl StructSignature {
+    /// Type of a field in a value of the struct, `vec<t> items` is
+    /// `vec<str>` in a `conveyor<str>`
+    fn field(self: &Self, index: usize, value: &TypeSignature) -> TypeSignature {
+        substitute(&self.fields[index].1, &self.generics, &self.bindings(value))
+    }
+
+    /// Type parameters of the struct bound to the generics of the value
+    fn bindings(self: &Self, value: &TypeSignature) -> HashMap<String, TypeSignature> {
+        self.generics
+            .iter()
+            .cloned()
+            .zip(value.generics.iter().cloned())
+            .collect()
+    }
+}
+
+/// Whether the types are the same, the unknown parts of them match anything,
+/// `vec<int>` and the type of `[]`
+fn matches(expected: &TypeSignature, found: &TypeSignature) -> bool {
+    if expected.is_unknown() || found.is_unknown() {
+        return true;
+    }
+    expected.data_type == found.data_type
+        && expected.generics.len() == found.generics.len()
+        && expected.generics
+            .iter()
+            .zip(found.generics.iter())
+            .all(|(expected, found)| matches(expected, found))
+}
+
+/// Signature of a native function that takes typed parameters, functions of
+/// a type take the value they are called on first, `push^vec`
+fn native_signature(native: &Native) -> Option<Signature> {
+    Some(Signature {
+        node: 0,
+        import: None,
+        receiver: false,
+        generics: native.generics.clone(),
+        owner: None,
+        params: native.params.clone()?,
+        returns: native.returns.clone(),
+    })
+}
+
+/// Basic types a value can be cast to besides its own type
+fn casts(data_type: &DataTypes) -> Vec<DataTypes> {
+    match data_type {
+        DataTypes::Int => vec![DataTypes::Flt, DataTypes::Bol, DataTypes::Chr, DataTypes::Str],
+        DataTypes::Flt
+        | DataTypes::Bol
+        | DataTypes::Chr => vec![DataTypes::Int, DataTypes::Str],
+        DataTypes::Str => vec![DataTypes::Int, DataTypes::Flt, DataTypes::Bol, DataTypes::Chr],
+        _ => vec![],
+    }
+}
+
+/// Struct nodes that extend another struct instead of declaring one,
+/// `^counter^limited;`
+fn is_extension(nodes: &[Node], node: &Node) -> bool {
+    matches!(node.token, TokenTypes::Struct(_)) && node.children
+        .iter()
+        .filter_map(|id| nodes.get(*id as usize))
+        .any(|child| child.token == TokenTypes::Implement)
+}
+
+/// Replaces the type parameters in `generics` by the types they are bound
+/// to, the ones that are not bound yet become unknown
+pub fn substitute(
+    signature: &TypeSignature,
+    generics: &[String],
+    bindings: &HashMap<String, TypeSignature>,
+) -> TypeSignature {
+    if let DataTypes::User(name) = &signature.data_type {
+        if generics.contains(name) {
+            return bindings.get(name).cloned().unwrap_or_default();
+        }
+    }
+    TypeSignature {
+        data_type: signature.data_type.clone(),
+        generics: signature.generics
+            .iter()
+            .map(|generic| substitute(generic, generics, bindings))
+            .collect(),
+    }
+}
+
+/// Binds the type parameters in `param` to the types in the same place in
+/// `found`, the first binding of a parameter is the one that holds
+fn unify(
+    param: &TypeSignature,
+    found: &TypeSignature,
+    generics: &[String],
+    bindings: &mut HashMap<String, TypeSignature>,
+) {
+    if found.is_unknown() {
+        return;
+    }
+    if let DataTypes::User(name) = &param.data_type {
+        if generics.contains(name) {
+            if !bindings.contains_key(name) {
+                bindings.insert(name.clone(), found.clone());
+            }
+            return;
+        }
+    }
+    if param.data_type == found.data_type {
+        for (param, found) in param.generics.iter().zip(found.generics.iter()) {
+            unify(param, found, generics, bindings);
+        }
+    }
+}
+
+/// Semantic pass over the node tree made by the parser, it resolves the
+/// declared types and infers the type of every expression
+pub struct Checker<'a> {
+    nodes: &'a [Node],
+    natives: &'a Natives,
+    functions: HashMap<String, Signature>,
+    structs: HashMap<String, StructSignature>,
+    scope: Scope,
+    /// Return type of the function being checked
+    returns: TypeSignature,
+    /// Struct that `self` refers to in the function being checked
+    implementing: Option<TypeSignature>,
+    /// Type parameters that can be used where the checker is
+    type_params: Vec<String>,
+    /// Types of the runtime imported from std
+    builtins: Vec<String>,
+    analysis: Analysis,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Checker<'a> {
+    pub fn check(
+        nodes: &'a [Node],
+        natives: &'a Natives,
+        imports: &Exports,
+    ) -> Result<Analysis, Vec<Diagnostic>> {
+        let mut checker = Self {
+            nodes,
+            natives,
+            functions: imports.functions.clone(),
+            structs: imports.structs.clone(),
+            scope: Scope::default(),
+            returns: TypeSignature::void(),
+            implementing: None,
+            type_params: vec![],
+            builtins: imports.builtins.clone(),
+            analysis: Analysis::default(),
+            diagnostics: vec![],
+        };
+        // Imported declarations were added with the imports
+        let functions: Vec<&Node> = declarations(nodes)
+            .into_iter()
+            .filter(|declaration| import_path(nodes, declaration).is_none())
+            .collect();
+        checker.collect_structs(&functions);
+        // Signatures first so calls can refer to functions declared later
+        for function in &functions {
+            if let Some(name) = function_name(nodes, function) {
+                if let Some(previous) = checker.functions.get(&name) {
+                    let diagnostic = Diagnostic::at_node(
+                        format!("Function `{}` is already defined", readable_name(&name)),
+                        function,
+                    );
+                    let note = match &previous.import {
+                        Some(import) => format!("it is imported from `{}`", module_of(import)),
+                        None => {
+                            let previous = &nodes[previous.node as usize];
+                            format!("first defined at {}:{}", previous.cursor.line, previous.cursor.column)
+                        }
+                    };
+                    checker.diagnostics.push(diagnostic.with_note(note));
+                    continue;
+                }
+                let signature = checker.signature(function);
+                checker.functions.insert(name, signature);
+            }
+        }
+        checker.check_overrides();
+        for function in &functions {
+            checker.check_function(function);
+        }
+        if !checker.diagnostics.is_empty() {
+            return Err(checker.diagnostics);
+        }
+        checker.collect_exports();
+        checker.collect_kinds();
+        checker.collect_params();
+        checker.collect_fields();
+        Ok(checker.analysis)
+    }
+
+    /// Exported structs take their functions with them
+    fn collect_exports(self: &mut Self) {
+        let exported = self.nodes
+            .first()
+            .map(|root| self.children(root))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|node| node.token == TokenTypes::Export)
+            .flat_map(|export| self.children(export));
+        let mut exports = Exports::default();
+        for declaration in exported {
+            match &declaration.token {
+                TokenTypes::Struct(name) => {
+                    if let Some(structure) = self.structs.get(name) {
+                        exports.structs.insert(name.clone(), structure.clone());
+                    }
+                }
+                TokenTypes::Function(_) => {
+                    let name = function_name(self.nodes, declaration).unwrap_or_default();
+                    if let Some(signature) = self.functions.get(&name) {
+                        exports.functions.insert(name, signature.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (name, signature) in &self.functions {
+            let structure = name.split_once('^').map(|(_, structure)| structure);
+            let exported = structure.is_some_and(|structure| exports.structs.contains_key(structure));
+            if exported && signature.import.is_none() {
+                exports.functions.insert(name.clone(), signature.clone());
+            }
+        }
+        self.analysis.exports = exports;
+    }
+
+    /// Structs can be used anywhere in the file, even before they are
+    /// declared
+    fn collect_structs(self: &mut Self, declarations: &[&Node]) {
+        for declaration in declarations {
+            let name = match &declaration.token {
+                TokenTypes::Struct(name) if !is_extension(self.nodes, declaration) => name.clone(),
+                _ => continue,
+            };
+            if self.builtins.contains(&name) {
+                self.diagnostics.push(Diagnostic::at_node(
+                    format!("Struct `{}` is already defined", name),
+                    declaration,
+                ).with_note("it is imported from `std`".to_string()));
+                continue;
+            }
+            if let Some(previous) = self.structs.get(&name) {
+                let note = match &previous.import {
+                    Some(module) => format!("it is imported from `{}`", module),
+                    None => {
+                        let previous = &self.nodes[previous.node as usize];
+                        format!("first defined at {}:{}", previous.cursor.line, previous.cursor.column)
+                    }
+                };
+                self.diagnostics.push(Diagnostic::at_node(
+                    format!("Struct `{}` is already defined", name),
+                    declaration,
+                ).with_note(note));
+                continue;
+            }
+            let mut fields: Vec<(String, TypeSignature)> = vec![];
+            for field in self.children(declaration) {
+                if field.token == TokenTypes::Generic {
+                    continue;
+                }
+                let declared = self.children(field)
+                    .into_iter()
+                    .find_map(|child| match &child.token {
+                        TokenTypes::Variable(field_name) => Some((child, field_name.clone())),
+                        _ => None,
+                    });
+                let (variable, field_name) = match declared {
+                    Some(declared) => declared,
+                    None => continue,
+                };
+                if fields.iter().any(|(existing, _)| *existing == field_name) {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        format!("Field `{}` is already declared in `{}`", field_name, name),
+                        variable,
+                    ));
+                    continue;
+                }
+                fields.push((field_name, self.resolve(field)));
+            }
+            self.structs.insert(name, StructSignature {
+                node: declaration.id,
+                generics: self.type_params(declaration),
+                fields,
+                extends: None,
+                import: None,
+            });
+        }
+        // Fields can only be checked once every struct is known
+        for declaration in declarations {
+            let name = match &declaration.token {
+                TokenTypes::Struct(name) if !is_extension(self.nodes, declaration) => name.clone(),
+                _ => continue,
+            };
+            self.type_params = self.type_params(declaration);
+            let mut invalid = vec![];
+            for field in self.children(declaration) {
+                if field.token == TokenTypes::Generic {
+                    continue;
+                }
+                let declared = self.resolve(field);
+                if !self.validate(&declared, field) {
+                    invalid.push(declared);
+                }
+            }
+            // Unknown fields are not reported again where they are used
+            if let Some(structure) = self.structs.get_mut(&name) {
+                for (_, field) in structure.fields.iter_mut() {
+                    if invalid.contains(field) {
+                        *field = TypeSignature::default();
+                    }
+                }
+            }
+        }
+        for declaration in declarations {
+            if is_extension(self.nodes, declaration) {
+                self.collect_extension(declaration);
+            }
+        }
+        self.type_params.clear();
+    }
+
+    /// `^counter^limited;` gives `limited` the functions of `counter`. Those
+    /// functions read the fields by their position, so `limited` has to start
+    /// with the fields of `counter`. Structs extending `vec` keep their items
+    /// in their first field of its type, `^vec<t>^conveyor<t>;`.
+    fn collect_extension(self: &mut Self, declaration: &Node) {
+        let base = match &declaration.token {
+            TokenTypes::Struct(base) => base.clone(),
+            _ => return,
+        };
+        let derived = self.children(declaration)
+            .into_iter()
+            .filter(|child| child.token == TokenTypes::Implement)
+            .flat_map(|implement| self.children(implement))
+            .next();
+        let derived_node = match derived {
+            Some(derived) => derived,
+            None => return,
+        };
+        let derived = self.resolve(derived_node);
+        // The type parameters of the extension are the ones given to the
+        // derived struct, `^box<t>^fancy<t>;`
+        self.type_params = derived.generics
+            .iter()
+            .filter_map(|generic| match &generic.data_type {
+                DataTypes::User(name) => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+        let basic = DataTypes::try_from(base.clone()).ok();
+        let mut extends = TypeSignature::new(basic.clone().unwrap_or(DataTypes::User(base.clone())));
+        extends.generics = self.children(declaration)
+            .into_iter()
+            .filter(|child| child.token == TokenTypes::Generic)
+            .flat_map(|generic| self.children(generic))
+            .map(|generic| self.resolve(generic))
+            .collect();
+        if basic.is_some_and(|basic| basic != DataTypes::Vec) {
+            self.diagnostics.push(Diagnostic::at_node(
+                format!("`{}` cannot be extended, only `vec` and structs declared in the program can", base),
+                declaration,
+            ));
+            return;
+        }
+        let derived_name = match &derived.data_type {
+            DataTypes::User(name) if self.structs.get(name).is_some_and(|s| s.import.is_some()) => {
+                self.diagnostics.push(Diagnostic::at_node(
+                    format!("`{}` cannot extend `{}`, it is imported from another file", derived, base),
+                    derived_node,
+                ));
+                return;
+            }
+            DataTypes::User(name) if self.structs.contains_key(name) => name.clone(),
+            _ => {
+                self.diagnostics.push(Diagnostic::at_node(
+                    format!("Cannot find struct `{}` to extend `{}` with", derived, base),
+                    derived_node,
+                ));
+                return;
+            }
+        };
+        if !self.validate(&extends, declaration) || !self.validate(&derived, derived_node) {
+            return;
+        }
+        // Written with the names of the extension, stored with the names of
+        // the derived struct
+        let structure = self.structs[&derived_name].clone();
+        let bindings: HashMap<String, TypeSignature> = self.type_params
+            .iter()
+            .cloned()
+            .zip(structure.generics
+                .iter()
+                .map(|generic| TypeSignature::new(DataTypes::User(generic.clone())))
+            )
+            .collect();
+        let extends = substitute(&extends, &self.type_params, &bindings);
+        if let Some(previous) = &structure.extends {
+            self.diagnostics.push(Diagnostic::at_node(
+                format!("`{}` already extends `{}`", derived_name, previous),
+                declaration,
+            ));
+            return;
+        }
+        if base == derived_name || self.upcast(&extends, &derived_name).is_some() {
+            self.diagnostics.push(Diagnostic::at_node(
+                format!("`{}` cannot extend `{}`, `{}` already extends it", derived_name, base, base),
+                declaration,
+            ));
+            return;
+        }
+        if extends.data_type == DataTypes::Vec {
+            if !structure.fields.iter().any(|(_, field)| *field == extends) {
+                self.diagnostics.push(Diagnostic::at_node(
+                    format!("`{}` needs a field of type `{}` to extend `vec`", derived_name, extends),
+                    declaration,
+                ).with_note("the items of the vec are kept in its first field of that type".to_string()));
+                return;
+            }
+            if let Some(structure) = self.structs.get_mut(&derived_name) {
+                structure.extends = Some(extends);
+            }
+            return;
+        }
+        let extended = match self.structs.get(&base) {
+            Some(extended) => extended.clone(),
+            None => return,
+        };
+        let fields = extended.fields.clone();
+        let inherited: Vec<TypeSignature> = (0..fields.len())
+            .map(|index| extended.field(index, &extends))
+            .collect();
+        let starts_with = fields.len() <= structure.fields.len() && fields
+            .iter()
+            .zip(inherited.iter())
+            .zip(structure.fields.iter())
+            .all(|(((name, _), field), (own_name, own_field))| {
+                name == own_name && (field == own_field || field.is_unknown())
+            });
+        if !starts_with {
+            let expected: Vec<String> = fields
+                .iter()
+                .zip(inherited.iter())
+                .map(|((name, _), field)| format!("{} {}", field, name))
+                .collect();
+            self.diagnostics.push(Diagnostic::at_node(
+                format!("`{}` has to start with the fields of `{}` to extend it", derived_name, base),
+                declaration,
+            ).with_note(format!("the first fields of `{}` have to be `{}`", derived_name, expected.join("; "))));
+            return;
+        }
+        if let Some(structure) = self.structs.get_mut(&derived_name) {
+            structure.extends = Some(extends);
+        }
+    }
+
+    /// `found` as the `target` struct it extends, `None` when it does not
+    /// extend it
+    fn upcast(self: &Self, found: &TypeSignature, target: &str) -> Option<TypeSignature> {
+        let mut current = found.clone();
+        // Bounded in case a cycle slipped through
+        for _ in 0..=self.structs.len() {
+            let name = match &current.data_type {
+                DataTypes::User(name) => name,
+                _ => return None,
+            };
+            if name == target {
+                return Some(current);
+            }
+            let structure = self.structs.get(name)?;
+            let extends = structure.extends.as_ref()?;
+            current = substitute(extends, &structure.generics, &structure.bindings(&current));
+        }
+        return None;
+    }
+
+    /// The vec that a value of a struct extending `vec` keeps its items in,
+    /// with the position of its field
+    fn items(self: &Self, found: &TypeSignature) -> Option<(TypeSignature, u32)> {
+        let mut current = found.clone();
+        for _ in 0..=self.structs.len() {
+            let name = match &current.data_type {
+                DataTypes::User(name) => name,
+                _ => return None,
+            };
+            let structure = self.structs.get(name)?;
+            let extends = structure.extends.as_ref()?;
+            if extends.data_type == DataTypes::Vec {
+                let index = structure.fields.iter().position(|(_, field)| field == extends)?;
+                return Some((structure.field(index, &current), index as u32));
+            }
+            current = substitute(extends, &structure.generics, &structure.bindings(&current));
+        }
+        return None;
+    }
+
+    /// Function that runs when `method` is called on the struct, its own one
+    /// or the one of the closest struct it extends
+    fn find_method(self: &Self, structure: &str, method: &str) -> Option<String> {
+        let mut structure = structure.to_string();
+        for _ in 0..=self.structs.len() {
+            let name = method_name(method, &structure);
+            if self.functions.contains_key(&name) {
+                return Some(name);
+            }
+            structure = match self.structs.get(&structure)?.extends.as_ref()?.data_type.clone() {
+                DataTypes::User(base) => base,
+                _ => return None,
+            };
+        }
+        return None;
+    }
+
+    /// Functions that replace one of the struct they extend have to be called
+    /// the same way
+    fn check_overrides(self: &mut Self) {
+        let mut overrides = vec![];
+        for (name, signature) in &self.functions {
+            let (method, structure) = match name.split_once('^') {
+                Some(split) if signature.import.is_none() => split,
+                _ => continue,
+            };
+            let own = match self.structs.get(structure) {
+                Some(declared) => TypeSignature {
+                    data_type: DataTypes::User(structure.to_string()),
+                    generics: declared.generics
+                        .iter()
+                        .map(|generic| TypeSignature::new(DataTypes::User(generic.clone())))
+                        .collect(),
+                },
+                None => continue,
+            };
+            let (replaced, receiver, previous_params, previous_returns) = match self.replaced(&own, method) {
+                Some(replaced) => replaced,
+                None => continue,
+            };
+            let same = match self.seen_from(signature, &own) {
+                Some((params, returns)) => {
+                    let returns_the_same = matches(&previous_returns, &returns) || match &previous_returns.data_type {
+                        DataTypes::User(previous) => self.upcast(&returns, previous)
+                            .is_some_and(|upcast| matches(&previous_returns, &upcast)),
+                        _ => false,
+                    };
+                    params.len() == previous_params.len()
+                        && params.iter().zip(previous_params.iter()).all(|(own, previous)| matches(own, previous))
+                        && returns_the_same
+                }
+                None => signature.params.len() == previous_params.len() + receiver as usize,
+            };
+            if receiver != signature.receiver || !same {
+                overrides.push((signature.node, name.clone(), replaced));
+            }
+        }
+        // Reported in the order they are written
+        overrides.sort_by_key(|(node, _, _)| *node);
+        for (node, name, replaced) in overrides {
+            let note = match self.functions.get(&replaced) {
+                Some(Signature { import: Some(import), .. }) => {
+                    format!("`{}` is imported from `{}`", readable_name(&replaced), module_of(import))
+                }
+                Some(previous) => {
+                    let previous = &self.nodes[previous.node as usize];
+                    format!(
+                        "`{}` is defined at {}:{}",
+                        readable_name(&replaced), previous.cursor.line, previous.cursor.column
+                    )
+                }
+                None => format!("`{}` is a function of the runtime", readable_name(&replaced)),
+            };
+            self.diagnostics.push(Diagnostic::at_node(
+                format!(
+                    "`{}` has to take the same parameters and return the same type as the `{}` it replaces",
+                    readable_name(&name), readable_name(&replaced)
+                ),
+                &self.nodes[node as usize],
+            ).with_note(note));
+        }
+    }
+
+    /// The function that `method` would call on `own` if it did not have its
+    /// own, whether it takes `self`, and its parameters after `self` and
+    /// return type in terms of the type parameters of `own`
+    fn replaced(
+        self: &Self,
+        own: &TypeSignature,
+        method: &str,
+    ) -> Option<(String, bool, Vec<TypeSignature>, TypeSignature)> {
+        let structure = match &own.data_type {
+            DataTypes::User(structure) => self.structs.get(structure)?,
+            _ => return None,
+        };
+        match &structure.extends.as_ref()?.data_type {
+            DataTypes::User(base) => {
+                let replaced = self.find_method(base, method)?;
+                let previous = &self.functions[&replaced];
+                let (params, returns) = self.seen_from(previous, own)
+                    .unwrap_or((previous.params.iter().skip(previous.receiver as usize).cloned().collect(), previous.returns.clone()));
+                Some((replaced, previous.receiver, params, returns))
+            }
+            // Natives of `vec` take the items and give back the struct
+            // instead of a new vec
+            DataTypes::Vec => {
+                let replaced = method_name(method, "vec");
+                let native = self.natives
+                    .get(&replaced)
+                    .and_then(native_signature)
+                    .filter(|native| !native.params.is_empty())?;
+                let (items, _) = self.items(own)?;
+                let mut bindings = HashMap::new();
+                unify(&native.params[0], &items, &native.generics, &mut bindings);
+                let params = native.params[1..]
+                    .iter()
+                    .map(|param| substitute(param, &native.generics, &bindings))
+                    .collect();
+                let mut returns = substitute(&native.returns, &native.generics, &bindings);
+                if returns == items {
+                    returns = own.clone();
+                }
+                Some((replaced, true, params, returns))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parameters after `self` and return type of a function of a struct,
+    /// in terms of the type parameters of `own`, which has or inherits it
+    fn seen_from(
+        self: &Self,
+        signature: &Signature,
+        own: &TypeSignature,
+    ) -> Option<(Vec<TypeSignature>, TypeSignature)> {
+        let owner = signature.owner.as_ref()?;
+        let upcast = match &owner.data_type {
+            DataTypes::User(owner) => self.upcast(own, owner)?,
+            _ => return None,
+        };
+        let names: Vec<String> = owner.generics
+            .iter()
+            .filter_map(|generic| match &generic.data_type {
+                DataTypes::User(name) => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+        let bindings: HashMap<String, TypeSignature> = names
+            .iter()
+            .cloned()
+            .zip(upcast.generics.iter().cloned())
+            .collect();
+        let params = signature.params
+            .iter()
+            .skip(signature.receiver as usize)
+            .map(|param| substitute(param, &names, &bindings))
+            .collect();
+        Some((params, substitute(&signature.returns, &names, &bindings)))
+    }
+
+    /// Whether a struct extending `structure` replaces the function that
+    /// `method` calls on it
+    fn is_replaced(self: &Self, structure: &str, method: &str, called: &str) -> bool {
+        self.structs.keys().any(|derived| {
+            derived != structure
+                && self.upcast(&TypeSignature::new(DataTypes::User(derived.clone())), structure).is_some()
+                && self.find_method(derived, method).is_some_and(|found| found != called)
+        })
+    }
+
+    /// The function each method calls on the structs of the file that extend
+    /// another struct, their values know their kind to call them
+    fn collect_kinds(self: &mut Self) {
+        let mut kinds = vec![];
+        for (name, structure) in &self.structs {
+            let extends_struct = structure.extends
+                .as_ref()
+                .is_some_and(|extends| matches!(extends.data_type, DataTypes::User(_)));
+            if structure.import.is_some() || !extends_struct {
+                continue;
+            }
+            let own = TypeSignature::new(DataTypes::User(name.clone()));
+            let mut methods: Vec<(String, String)> = self.functions
+                .iter()
+                .filter(|(_, signature)| signature.receiver)
+                .filter_map(|(function, _)| function.split_once('^'))
+                .filter(|(_, owner)| self.upcast(&own, owner).is_some())
+                .filter_map(|(method, _)| {
+                    let called = self.find_method(name, method)?;
+                    let called = self.functions[&called].import.clone().unwrap_or(called);
+                    Some((method.to_string(), called))
+                })
+                .collect();
+            methods.sort();
+            methods.dedup();
+            kinds.push((name.clone(), methods));
+        }
+        kinds.sort();
+        self.analysis.kinds = kinds;
+    }
+
+    fn collect_params(self: &mut Self) {
+        self.analysis.params = self.functions
+            .iter()
+            .filter(|(_, signature)| signature.import.is_none())
+            .map(|(name, signature)| (name.clone(), Params {
+                generics: signature.generics.clone(),
+                types: signature.params.clone(),
+            }))
+            .collect();
+    }
+
+    fn collect_fields(self: &mut Self) {
+        self.analysis.structs = self.structs
+            .iter()
+            .map(|(name, structure)| (name.clone(), Fields {
+                generics: structure.generics.clone(),
+                types: structure.fields.iter().map(|(_, field)| field.clone()).collect(),
+            }))
+            .collect();
+    }
+
+    /// Names of the type parameters declared in the `Generic` of a struct or
+    /// function, `^conveyor<t>` declares `t`
+    fn type_params(self: &Self, node: &Node) -> Vec<String> {
+        self.children(node)
+            .into_iter()
+            .filter(|child| child.token == TokenTypes::Generic)
+            .flat_map(|generic| self.children(generic))
+            .filter_map(|param| match &param.token {
+                TokenTypes::Type(DataTypes::User(name)) => Some(name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn signature(self: &mut Self, function: &Node) -> Signature {
+        self.implementing = self.implementing(function);
+        let mut signature = Signature {
+            node: function.id,
+            import: None,
+            receiver: false,
+            generics: self.type_params(function),
+            owner: self.implementing.clone(),
+            params: vec![],
+            returns: TypeSignature::void(),
+        };
+        for child in self.children(function) {
+            match &child.token {
+                // `#new^conveyor<t>` can use `t`
+                TokenTypes::Implement => {
+                    for structure in self.children(child) {
+                        signature.generics.extend(self.type_params(structure));
+                    }
+                }
+                TokenTypes::Params => {
+                    let params = self.children(child);
+                    signature.receiver = params
+                        .first()
+                        .is_some_and(|param| param.token == TokenTypes::Type(DataTypes::Slf));
+                    signature.params = params
+                        .into_iter()
+                        .map(|param| self.resolve(param))
+                        .collect();
+                }
+                TokenTypes::Type(_) => {
+                    signature.returns = self.resolve(child);
+                }
+                _ => {}
+            }
+        }
+        return signature;
+    }
+
+    /// The struct of `#name^struct` functions
+    fn implementing(self: &Self, function: &Node) -> Option<TypeSignature> {
+        self.children(function)
+            .into_iter()
+            .filter(|child| child.token == TokenTypes::Implement)
+            .flat_map(|implement| self.children(implement))
+            .map(|structure| self.resolve(structure))
+            .next()
+    }
+
+    fn check_function(self: &mut Self, function: &Node) {
+        let name = match function_name(self.nodes, function) {
+            Some(name) => name,
+            None => return,
+        };
+        // Duplicates were already reported
+        match self.functions.get(&name) {
+            Some(signature) if signature.node == function.id && signature.import.is_none() => {
+                self.returns = signature.returns.clone();
+                self.type_params = signature.generics.clone();
+            }
+            _ => return,
+        }
+        self.scope = Scope::default();
+        self.implementing = self.implementing(function);
+        for child in self.children(function) {
+            match &child.token {
+                TokenTypes::Implement => {
+                    for structure in self.children(child) {
+                        let implementing = self.resolve(structure);
+                        match implementing.data_type {
+                            DataTypes::User(_) => {
+                                self.validate(&implementing, structure);
+                            }
+                            _ => self.diagnostics.push(Diagnostic::at_node(
+                                format!("Functions can only be added to structs, not `{}`", implementing),
+                                structure,
+                            )),
+                        }
+                    }
+                }
+                TokenTypes::Params => {
+                    for param in self.children(child) {
+                        self.declare(param);
+                    }
+                }
+                TokenTypes::Type(_) => {
+                    let returns = self.resolve(child);
+                    self.validate(&returns, child);
+                }
+                TokenTypes::Body => {
+                    self.check_body(child);
+                    let returns = self.returns.clone();
+                    if returns.data_type != DataTypes::Void
+                        && !returns.is_unknown()
+                        && !self.always_returns(child) {
+                        self.diagnostics.push(Diagnostic::at_node(
+                            format!("Not all paths of `{}` return a value of type `{}`", name, returns),
+                            function,
+                        ).with_note("the body has to end with a `<<`".to_string()));
+                    }
+                }
+                TokenTypes::ShortReturn => self.check_return(child),
+                _ => {}
+            }
+        }
+        if name == "main" {
+            self.check_main(function);
+        }
+    }
+
+    /// `#main` is called with the arguments of the program and gives back
+    /// its exit code
+    fn check_main(self: &mut Self, function: &Node) {
+        let signature = &self.functions["main"];
+        let arguments = TypeSignature {
+            data_type: DataTypes::Vec,
+            generics: vec![TypeSignature::new(DataTypes::Str)],
+        };
+        let takes_arguments = match signature.params.as_slice() {
+            [] => true,
+            [param] => matches(&arguments, param),
+            _ => false,
+        };
+        let returns_code = signature.returns.is_unknown()
+            || matches!(signature.returns.data_type, DataTypes::Int | DataTypes::Void);
+        if !takes_arguments || !returns_code {
+            self.diagnostics.push(Diagnostic::at_node(
+                "`main` has to take a `vec<str>` or nothing and return an `int` or nothing".to_string(),
+                function,
+            ).with_note("it is called with the arguments of the program and returns its exit code".to_string()));
+        }
+    }
+
+    /// Whether the body cannot be left without a `<<`. Ifs can be skipped,
+    /// loops are only left by a break.
+    fn always_returns(self: &Self, body: &Node) -> bool {
+        self.children(body).into_iter().any(|child| match &child.token {
+            TokenTypes::Return => true,
+            TokenTypes::Loop => !self.children(child).into_iter().any(|body| self.breaks(body)),
+            _ => false,
+        })
+    }
+
+    /// Whether the body breaks out of the loop it is in
+    fn breaks(self: &Self, body: &Node) -> bool {
+        self.children(body).into_iter().any(|child| match &child.token {
+            TokenTypes::Break => true,
+            TokenTypes::If => self.children(child)
+                .into_iter()
+                .any(|part| part.token == TokenTypes::Body && self.breaks(part)),
+            _ => false,
+        })
+    }
+
+    fn check_body(self: &mut Self, body: &Node) {
+        self.scope.push();
+        for child in self.children(body) {
+            match &child.token {
+                TokenTypes::Statement => self.check_statement(child),
+                TokenTypes::Return => self.check_return(child),
+                TokenTypes::If => {
+                    for part in self.children(child) {
+                        match &part.token {
+                            TokenTypes::Condition => self.check_condition(part),
+                            TokenTypes::Body => self.check_body(part),
+                            _ => {}
+                        }
+                    }
+                }
+                TokenTypes::Loop => {
+                    for part in self.children(child) {
+                        self.check_body(part);
+                    }
+                }
+                TokenTypes::Call(_) => {
+                    self.expression(child);
+                }
+                _ => {}
+            }
+        }
+        self.scope.pop();
+    }
+
+    fn check_condition(self: &mut Self, condition: &Node) {
+        for expression in self.children(condition) {
+            let found = self.expression(expression);
+            self.expect(&TypeSignature::new(DataTypes::Bol), &found, expression);
+        }
+    }
+
+    fn check_return(self: &mut Self, node: &Node) {
+        let expression = self.children(node)
+            .into_iter()
+            .flat_map(|statement| self.children(statement))
+            .next();
+        let expected = self.returns.clone();
+        match expression {
+            Some(expression) if expected.data_type == DataTypes::Void => {
+                let found = self.expression(expression);
+                if !found.is_unknown() {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        format!("Cannot return `{}` from a function without a return type", found),
+                        expression,
+                    ).with_note("the return type goes after the parameters, `#f () int {`".to_string()));
+                }
+            }
+            Some(expression) => self.check_value(&expected, expression),
+            None => {
+                if expected.data_type != DataTypes::Void && !expected.is_unknown() {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        format!("Expected a value of type `{}` to be returned", expected),
+                        node,
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_statement(self: &mut Self, statement: &Node) {
+        let children = self.children(statement);
+        let target = match children.first() {
+            Some(target) => *target,
+            None => return,
+        };
+        let operator = children.get(1).and_then(|node| match &node.token {
+            TokenTypes::Operator(operator) => Some(operator.clone()),
+            _ => None,
+        });
+        let value = children.get(2).copied();
+        match (&target.token, operator) {
+            // Declaration, `int a = 1`
+            (TokenTypes::Type(_), _) => {
+                let mut declared = self.resolve(target);
+                if !self.validate(&declared, target) {
+                    declared = TypeSignature::default();
+                }
+                match value {
+                    Some(value) => self.check_value(&declared, value),
+                    // Structs have no default value, maps are empty
+                    None if matches!(declared.data_type, DataTypes::User(_)) && !self.is_map(&declared) => {
+                        self.diagnostics.push(Diagnostic::at_node(
+                            format!("Variables of type `{}` have to be given a value", declared),
+                            target,
+                        ));
+                    }
+                    None => {}
+                }
+                self.declare_as(target, declared);
+            }
+            // Reassignment, `a = 1`, `a += 1`, `a++`
+            (TokenTypes::Variable(variable), Some(operator)) => {
+                if self.scope.lookup(variable).is_none() {
+                    let mut diagnostic = Diagnostic::at_node(
+                        format!("Cannot find `{}` in this scope", variable),
+                        target,
+                    );
+                    let found = value.map(|value| self.expression(value)).unwrap_or_default();
+                    if operator == Operators::Ass {
+                        let declared = match found.is_unknown() {
+                            true => "type".to_string(),
+                            false => found.to_string(),
+                        };
+                        diagnostic = diagnostic.with_note(format!(
+                            "declare it with its type first, `{} {} = ...`", declared, variable
+                        ));
+                    }
+                    self.diagnostics.push(diagnostic);
+                    return;
+                }
+                let declared = self.lookup(variable, target);
+                // Only numbers are changed in place, anything else is
+                // reassigned with a new value
+                let is_in_place = operator != Operators::Ass;
+                if is_in_place && !declared.is_numeric() && !declared.is_unknown() {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        format!("Cannot use `{}` on a value of type `{}`", operator.symbol(), declared),
+                        target,
+                    ).with_note(format!(
+                        "only `int` and `flt` variables can be changed in place, reassign it instead, `{} = ...`",
+                        variable
+                    )));
+                    if let Some(value) = value {
+                        self.expression(value);
+                    }
+                    return;
+                }
+                match (&operator, value) {
+                    (Operators::Ass, Some(value)) => self.check_value(&declared, value),
+                    (Operators::AssAdd | Operators::AssSub, Some(value)) => {
+                        let found = self.expression(value);
+                        match self.binary(&Operators::Add, &declared, &found) {
+                            Some(_) => self.expect(&declared, &found, value),
+                            None => self.mismatched_operator(&operator, &declared, &found, target),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {
+                self.expression(target);
+                self.check_unused(target);
+            }
+        }
+    }
+
+    /// Vecs and maps are immutable, their functions give back a new value
+    /// that is lost when they are called as a statement, `list.push(4);`
+    fn check_unused(self: &mut Self, target: &Node) {
+        let method = match &target.token {
+            TokenTypes::MethodCall(method) => method,
+            _ => return,
+        };
+        let builtin = match self.analysis.methods.get(&target.id) {
+            Some(called) if self.natives.contains(called) => called.split_once('^'),
+            _ => None,
+        };
+        let builtin = match builtin {
+            Some((_, builtin)) => builtin,
+            None => return,
+        };
+        let receiver = self.children(target).first().copied();
+        let receiver_type = receiver
+            .and_then(|receiver| self.analysis.types.get(&receiver.id))
+            .cloned()
+            .unwrap_or_default();
+        let returned = self.analysis.types.get(&target.id).cloned().unwrap_or_default();
+        let mut diagnostic = Diagnostic::at_node(
+            format!("The result of `{}` is not used", method),
+            target,
+        );
+        if returned == receiver_type {
+            let rebinding = match receiver.map(|receiver| &receiver.token) {
+                Some(TokenTypes::Variable(variable)) => format!(
+                    ", rebind it, `{} = {}.{}(...)`", variable, variable, method
+                ),
+                _ => String::new(),
+            };
+            diagnostic = diagnostic.with_note(format!(
+                "{}s cannot be changed in place, `{}` gives back a new one{}",
+                builtin, method, rebinding
+            ));
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Infers the type of the expression, reporting any misuse of types
+    /// found in it
+    fn expression(self: &mut Self, node: &Node) -> TypeSignature {
+        let found = match &node.token {
+            TokenTypes::LiteralInt(_) => TypeSignature::new(DataTypes::Int),
+            TokenTypes::LiteralFloat(_) => TypeSignature::new(DataTypes::Flt),
+            TokenTypes::LiteralString(_) => TypeSignature::new(DataTypes::Str),
+            TokenTypes::LiteralChar(_) => TypeSignature::new(DataTypes::Chr),
+            TokenTypes::LiteralBool(_) => TypeSignature::new(DataTypes::Bol),
+            TokenTypes::Variable(variable) => self.lookup(variable, node),
+            TokenTypes::Group => match self.children(node).first() {
+                Some(inner) => self.expression(inner),
+                None => TypeSignature::default(),
+            },
+            TokenTypes::Unary(operator) => {
+                let operand = match self.children(node).first() {
+                    Some(operand) => self.expression(operand),
+                    None => TypeSignature::default(),
+                };
+                if operand.is_numeric() || operand.is_unknown() {
+                    operand
+                } else {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        format!("Cannot apply unary `{}` to a value of type `{}`", operator.symbol(), operand),
+                        node,
+                    ));
+                    TypeSignature::default()
+                }
+            }
+            TokenTypes::Binary(operator) => {
+                let children = self.children(node);
+                let mut operands = vec![];
+                for child in children {
+                    operands.push(self.expression(child));
+                }
+                let left = operands.first().cloned().unwrap_or_default();
+                let right = operands.get(1).cloned().unwrap_or_default();
+                match self.binary(operator, &left, &right) {
+                    Some(found) => found,
+                    None => {
+                        self.mismatched_operator(operator, &left, &right, node);
+                        TypeSignature::default()
+                    }
+                }
+            }
+            TokenTypes::Call(name) => self.call(name, node),
+            TokenTypes::MethodCall(method) => {
+                let children = self.children(node);
+                let receiver = match children.first() {
+                    Some(receiver) => self.expression(receiver),
+                    None => TypeSignature::default(),
+                };
+                let arguments: Vec<&Node> = children
+                    .into_iter()
+                    .filter(|child| child.token == TokenTypes::CallParams)
+                    .flat_map(|params| self.children(params))
+                    .collect();
+                self.method_call(&receiver, method, arguments, node)
+            }
+            // `list[0]` is `list.get(0)` for structs
+            TokenTypes::Index => {
+                let children = self.children(node);
+                let mut operand = match children.first() {
+                    Some(operand) => self.expression(operand),
+                    None => TypeSignature::default(),
+                };
+                if self.is_map(&operand) {
+                    return self.method_call(&operand, "get", children[1..].to_vec(), node);
+                }
+                // Structs extending `vec` without a `get` of their own are
+                // indexed like their items
+                let inherited = match &operand.data_type {
+                    DataTypes::User(structure) if self.find_method(structure, "get").is_none() => {
+                        self.items(&operand)
+                    }
+                    _ => None,
+                };
+                if let (Some((items, field)), Some(indexed)) = (inherited, children.first()) {
+                    self.analysis.items.insert(indexed.id, field);
+                    operand = items;
+                }
+                // Strings are indexed by character, `text[0]` is a `chr`
+                if operand.data_type == DataTypes::Vec || operand.data_type == DataTypes::Str {
+                    for index in &children[1..] {
+                        let found = self.expression(index);
+                        self.expect(&TypeSignature::new(DataTypes::Int), &found, index);
+                    }
+                    let found = match operand.data_type {
+                        DataTypes::Str => TypeSignature::new(DataTypes::Chr),
+                        _ => operand.generics.first().cloned().unwrap_or_default(),
+                    };
+                    self.analysis.types.insert(node.id, found.clone());
+                    return found;
+                }
+                let indexable = match &operand.data_type {
+                    DataTypes::User(structure) => self.find_method(structure, "get").is_some(),
+                    _ => false,
+                };
+                if indexable || operand.is_unknown() {
+                    self.method_call(&operand, "get", children[1..].to_vec(), node)
+                } else {
+                    let mut diagnostic = Diagnostic::at_node(
+                        format!("Cannot index into a value of type `{}`", operand),
+                        node,
+                    );
+                    if let DataTypes::User(structure) = &operand.data_type {
+                        diagnostic = diagnostic.with_note(format!(
+                            "structs are indexed with their `get` function, `#get^{} (self self, int index)`",
+                            structure
+                        ));
+                    }
+                    self.diagnostics.push(diagnostic);
+                    for index in &children[1..] {
+                        self.expression(index);
+                    }
+                    TypeSignature::default()
+                }
+            }
+            TokenTypes::Access(field) => {
+                let operand = match self.children(node).first() {
+                    Some(operand) => self.expression(operand),
+                    None => TypeSignature::default(),
+                };
+                self.access(&operand, field, node)
+            }
+            TokenTypes::VecLiteral => self.vec_literal(&TypeSignature::default(), node),
+            TokenTypes::Cast => self.cast(node),
+            TokenTypes::StructLiteral => match self.named_struct(node) {
+                Some(named) => {
+                    self.struct_literal(&named, node);
+                    named
+                }
+                None => {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        "Cannot tell the struct of this literal".to_string(),
+                        node,
+                    ).with_note("give it a type first, `person p = { ... }`".to_string()));
+                    TypeSignature::default()
+                }
+            },
+            _ => TypeSignature::default(),
+        };
+        self.analysis.types.insert(node.id, found.clone());
+        return found;
+    }
+
+    /// The type of `left operator right`, `None` when the operator cannot be
+    /// used with those types
+    fn binary(
+        self: &Self,
+        operator: &Operators,
+        left: &TypeSignature,
+        right: &TypeSignature,
+    ) -> Option<TypeSignature> {
+        if left.is_unknown() || right.is_unknown() {
+            return Some(TypeSignature::default());
+        }
+        let bol = TypeSignature::new(DataTypes::Bol);
+        match operator {
+            Operators::Add
+                if left.data_type == DataTypes::Str && right.data_type == DataTypes::Str => {
+                Some(left.clone())
+            }
+            Operators::Add
+            | Operators::Sub
+            | Operators::Mul
+            | Operators::Div
+            | Operators::Mod => {
+                if left.is_numeric() && left == right {
+                    return Some(left.clone());
+                }
+                None
+            }
+            Operators::BitOr
+            | Operators::BitAnd
+            | Operators::BitLeft
+            | Operators::BitRight => {
+                if left.data_type == DataTypes::Int && left == right {
+                    return Some(left.clone());
+                }
+                None
+            }
+            Operators::Lt
+            | Operators::Gt
+            | Operators::EquGt
+            | Operators::EquLt => {
+                let ordered = left.is_numeric() || left.data_type == DataTypes::Chr;
+                if ordered && left == right {
+                    return Some(bol);
+                }
+                None
+            }
+            Operators::Equ
+            | Operators::NotEqu => {
+                if left == right {
+                    return Some(bol);
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn mismatched_operator(
+        self: &mut Self,
+        operator: &Operators,
+        left: &TypeSignature,
+        right: &TypeSignature,
+        node: &Node,
+    ) {
+        let mut diagnostic = Diagnostic::at_node(
+            format!("Cannot use `{}` between `{}` and `{}`", operator.symbol(), left, right),
+            node,
+        );
+        if left.is_numeric() && right.is_numeric() {
+            diagnostic = diagnostic.with_note(
+                "numbers of different types have to be cast first".to_string()
+            );
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn call(self: &mut Self, name: &str, node: &Node) -> TypeSignature {
+        let arguments: Vec<&Node> = self.children(node)
+            .into_iter()
+            .filter(|child| child.token == TokenTypes::CallParams)
+            .flat_map(|params| self.children(params))
+            .collect();
+        // `limited:new()` can be the `counter:new()` it extends
+        let inherited = name
+            .split_once('^')
+            .filter(|_| !self.functions.contains_key(name))
+            .and_then(|(function, structure)| self.find_method(structure, function));
+        let signature = match self.functions.get(inherited.as_deref().unwrap_or(name)) {
+            Some(signature) => {
+                let called = signature.import.clone().or(inherited);
+                if let Some(called) = called {
+                    self.analysis.methods.insert(node.id, called);
+                }
+                signature.clone()
+            }
+            None => match self.natives.get(name).map(|native| (native_signature(native), native)) {
+                Some((Some(signature), _)) => signature,
+                Some((None, native)) => {
+                    let returns = native.returns.clone();
+                    if name == "print" || name == "format" {
+                        return self.format_call(name, returns, &arguments, node);
+                    }
+                    // Takes anything, it checks its arguments when called
+                    for argument in arguments {
+                        self.expression(argument);
+                    }
+                    return returns;
+                }
+                None => {
+                    for argument in arguments {
+                        self.expression(argument);
+                    }
+                    let message = match name.split_once('^') {
+                    Some((function, structure)) => {
+                        format!("`{}` has no function `{}`", structure, function)
+                    }
+                    None => format!("Cannot find function `{}`", name),
+                };
+                    self.diagnostics.push(Diagnostic::at_node(message, node));
+                    return TypeSignature::default();
+                }
+            },
+        };
+        return self.arguments(name, &signature, HashMap::new(), &signature.params, arguments, node);
+    }
+
+    /// `print("{}:{}", a, b)` and `format`, the placeholders of the format
+    /// are counted when it is a literal. A single value that is not a str is
+    /// used as it is.
+    fn format_call(
+        self: &mut Self,
+        name: &str,
+        returns: TypeSignature,
+        arguments: &[&Node],
+        node: &Node,
+    ) -> TypeSignature {
+        let mut found = vec![];
+        for argument in arguments {
+            found.push(self.expression(argument));
+        }
+        let format = match arguments.first() {
+            Some(format) if arguments.len() > 1 || found[0].data_type == DataTypes::Str => *format,
+            Some(_) => return returns,
+            None if name == "format" => {
+                self.diagnostics.push(Diagnostic::at_node(
+                    "`format` expects a format and its values".to_string(),
+                    node,
+                ));
+                return returns;
+            }
+            None => return returns,
+        };
+        self.expect(&TypeSignature::new(DataTypes::Str), &found[0], format);
+        let literal = match &format.token {
+            TokenTypes::LiteralString(literal) => literal,
+            _ => return returns,
+        };
+        let diagnostic = match format_pieces(literal) {
+            Ok(pieces) if pieces.len() == arguments.len() => None,
+            Ok(pieces) => Some(Diagnostic::at_node(
+                format!(
+                    "Expected {} values for the placeholders of the format, found {}",
+                    pieces.len() - 1, arguments.len() - 1
+                ),
+                format,
+            )),
+            Err(message) => Some(Diagnostic::at_node(message, format)),
+        };
+        if let Some(diagnostic) = diagnostic {
+            self.diagnostics.push(diagnostic.with_note(
+                "each `{}` takes the next value, `{{` and `}}` are the braces themselves".to_string()
+            ));
+        }
+        return returns;
+    }
+
+    /// `employee.nameplate()` calls `nameplate^person` with `employee` as its
+    /// first argument
+    fn method_call(
+        self: &mut Self,
+        receiver: &TypeSignature,
+        method: &str,
+        arguments: Vec<&Node>,
+        node: &Node,
+    ) -> TypeSignature {
+        // Natives of the type of the receiver, `list.push(4)` is `push^vec`
+        let native = self.builtin(receiver)
+            .map(|builtin| method_name(method, builtin))
+            .and_then(|name| self.natives
+                .get(&name)
+                .and_then(native_signature)
+                .filter(|signature| !signature.params.is_empty())
+                .map(|signature| (name, signature))
+            );
+        if let Some((name, signature)) = native {
+            self.analysis.methods.insert(node.id, name);
+            let mut bindings = HashMap::new();
+            unify(&signature.params[0], receiver, &signature.generics, &mut bindings);
+            return self.arguments(method, &signature, bindings, &signature.params[1..], arguments, node);
+        }
+        let structure = match &receiver.data_type {
+            DataTypes::User(structure) => structure.clone(),
+            _ => {
+                if !receiver.is_unknown() {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        format!("`{}` has no method `{}`", receiver, method),
+                        node,
+                    ));
+                }
+                for argument in arguments {
+                    self.expression(argument);
+                }
+                return TypeSignature::default();
+            }
+        };
+        // Functions of `vec` run on the items of the structs extending it
+        let inherited = match self.find_method(&structure, method) {
+            Some(_) => None,
+            None => self.items(receiver).and_then(|(items, field)| self.natives
+                .get(&method_name(method, "vec"))
+                .and_then(native_signature)
+                .filter(|signature| !signature.params.is_empty())
+                .map(|signature| (items, field, signature))
+            ),
+        };
+        if let Some((items, field, signature)) = inherited {
+            self.analysis.methods.insert(node.id, method_name(method, "vec"));
+            let mut bindings = HashMap::new();
+            unify(&signature.params[0], &items, &signature.generics, &mut bindings);
+            let returns = self.arguments(method, &signature, bindings, &signature.params[1..], arguments, node);
+            // A new vec goes back in the struct
+            if returns.data_type == DataTypes::Vec && matches(&items, &returns) {
+                self.analysis.rewrapped.insert(node.id, field);
+                return receiver.clone();
+            }
+            if let Some(items) = self.children(node).first() {
+                self.analysis.items.insert(items.id, field);
+            }
+            return returns;
+        }
+        let name = self.find_method(&structure, method)
+            .unwrap_or_else(|| method_name(method, &structure));
+        if self.is_replaced(&structure, method, &name) {
+            self.analysis.dispatched.insert(node.id);
+        }
+        let signature = match self.functions.get(&name) {
+            Some(signature) if signature.receiver => signature.clone(),
+            found => {
+                let mut diagnostic = Diagnostic::at_node(
+                    format!("`{}` has no method `{}`", receiver, method),
+                    node,
+                );
+                if found.is_some() {
+                    diagnostic = diagnostic.with_note(format!(
+                        "it does not take `self`, call it with `{}:{}()`",
+                        structure, method
+                    ));
+                }
+                self.diagnostics.push(diagnostic);
+                for argument in arguments {
+                    self.expression(argument);
+                }
+                return TypeSignature::default();
+            }
+        };
+        // The receiver binds the type parameters of its struct, as the struct
+        // that has the function when it is inherited
+        let receiver = match name.split_once('^') {
+            Some((_, owner)) => self.upcast(receiver, owner).unwrap_or(receiver.clone()),
+            None => receiver.clone(),
+        };
+        let called = signature.import.clone().unwrap_or(name);
+        self.analysis.methods.insert(node.id, called);
+        let mut bindings = HashMap::new();
+        unify(&signature.params[0], &receiver, &signature.generics, &mut bindings);
+        return self.arguments(method, &signature, bindings, &signature.params[1..], arguments, node);
+    }
+
+    /// Checks the arguments of a call against the parameters of the function,
+    /// returning what the function returns. Type parameters are bound by the
+    /// arguments in order, the later ones have to agree with them.
+    fn arguments(
+        self: &mut Self,
+        name: &str,
+        signature: &Signature,
+        mut bindings: HashMap<String, TypeSignature>,
+        params: &[TypeSignature],
+        arguments: Vec<&Node>,
+        node: &Node,
+    ) -> TypeSignature {
+        let name = readable_name(name);
+        if arguments.len() != params.len() {
+            self.diagnostics.push(Diagnostic::at_node(
+                format!(
+                    "`{}` expects {} arguments, found {}",
+                    name, params.len(), arguments.len()
+                ),
+                node,
+            ));
+        }
+        let generics = &signature.generics;
+        for (index, argument) in arguments.into_iter().enumerate() {
+            let param = match params.get(index) {
+                Some(param) => param,
+                None => {
+                    self.expression(argument);
+                    continue;
+                }
+            };
+            if argument.token == TokenTypes::StructLiteral && !self.is_named(argument) {
+                let expected = substitute(param, generics, &bindings);
+                self.check_value(&expected, argument);
+                continue;
+            }
+            let found = match argument.token {
+                TokenTypes::VecLiteral => {
+                    let expected = substitute(param, generics, &bindings);
+                    self.vec_literal(&expected, argument)
+                }
+                _ => self.expression(argument),
+            };
+            let upcast = match &param.data_type {
+                DataTypes::User(name) if !generics.contains(name) => self.upcast(&found, name),
+                DataTypes::Vec => self.items(&found).map(|(items, _)| items),
+                _ => None,
+            };
+            unify(param, upcast.as_ref().unwrap_or(&found), generics, &mut bindings);
+            let expected = substitute(param, generics, &bindings);
+            let reported = self.diagnostics.len();
+            self.expect(&expected, &found, argument);
+            // Point at where the type parameter got its type
+            if let (Some(diagnostic), DataTypes::User(name)) =
+                (self.diagnostics.get_mut(reported), &param.data_type) {
+                if generics.contains(name) {
+                    diagnostic.notes.push(format!(
+                        "`{}` is `{}` because of the previous arguments", name, expected
+                    ));
+                }
+            }
+        }
+        return substitute(&signature.returns, generics, &bindings);
+    }
+
+    /// Type of the field read from a value of the `operand` type
+    fn access(self: &mut Self, operand: &TypeSignature, field: &str, node: &Node) -> TypeSignature {
+        if operand.is_unknown() {
+            return TypeSignature::default();
+        }
+        // Runs in the runtime, `list.length`
+        let builtin = match operand.data_type {
+            DataTypes::Vec => Some("vec"),
+            DataTypes::Str => Some("str"),
+            _ => None,
+        };
+        if let Some(builtin) = builtin.filter(|_| field == "length") {
+            self.analysis.methods.insert(node.id, method_name(field, builtin));
+            return TypeSignature::new(DataTypes::Int);
+        }
+        // Keys of a map, `info.name`
+        if self.is_map(operand) {
+            let key = operand.generics.first().cloned().unwrap_or_default();
+            if key.data_type == DataTypes::Str || key.is_unknown() {
+                return operand.generics.get(1).cloned().unwrap_or_default();
+            }
+            self.diagnostics.push(Diagnostic::at_node(
+                format!("`{}` has no field `{}`", operand, field),
+                node,
+            ).with_note("only maps with `str` keys can be read with `.key`".to_string()));
+            return TypeSignature::default();
+        }
+        let found = match &operand.data_type {
+            DataTypes::User(name) => self.structs
+                .get(name)
+                .and_then(|declared| declared.fields
+                    .iter()
+                    .position(|(existing, _)| existing == field)
+                    .map(|index| (index, declared.field(index, operand)))
+                ),
+            _ => None,
+        };
+        // `todo.length` is the length of the items of a struct extending
+        // `vec`
+        let inherited = self.items(operand).filter(|_| field == "length");
+        match (found, inherited) {
+            (Some((index, found)), _) => {
+                self.analysis.fields.insert(node.id, index as u32);
+                found
+            }
+            (None, Some((_, index))) => {
+                self.analysis.methods.insert(node.id, method_name(field, "vec"));
+                if let Some(items) = self.children(node).first() {
+                    self.analysis.items.insert(items.id, index);
+                }
+                TypeSignature::new(DataTypes::Int)
+            }
+            (None, None) => {
+                self.diagnostics.push(Diagnostic::at_node(
+                    format!("`{}` has no field `{}`", operand, field),
+                    node,
+                ));
+                TypeSignature::default()
+            }
+        }
+    }
+
+    /// `value~type`, basic types convert into each other and casting to any
+    /// other type gives its type to the value, `[]~vec<str>`
+    fn cast(self: &mut Self, node: &Node) -> TypeSignature {
+        let children = self.children(node);
+        let (value, target_node) = match (children.first(), children.get(1)) {
+            (Some(value), Some(target)) => (*value, *target),
+            _ => return TypeSignature::default(),
+        };
+        let target = self.resolve(target_node);
+        if !self.validate(&target, target_node) {
+            self.expression(value);
+            return TypeSignature::default();
+        }
+        if casts(&target.data_type).is_empty() {
+            self.check_value(&target, value);
+            return target;
+        }
+        let found = self.expression(value);
+        if found.is_unknown()
+            || found.data_type == target.data_type
+            || casts(&found.data_type).contains(&target.data_type) {
+            return target;
+        }
+        let convertible: Vec<String> = casts(&found.data_type)
+            .iter()
+            .map(|data_type| format!("`{}`", TypeSignature::new(data_type.clone())))
+            .collect();
+        let note = match convertible.split_last() {
+            Some((last, rest)) => format!("`{}` can be cast to {} and {}", found, rest.join(", "), last),
+            None => "only `int`, `flt`, `bol`, `chr` and `str` can be cast into each other".to_string(),
+        };
+        self.diagnostics.push(Diagnostic::at_node(
+            format!("Cannot cast a value of type `{}` to `{}`", found, target),
+            node,
+        ).with_note(note));
+        return target;
+    }
+
+    /// Checks an expression where a value of the `expected` type is needed,
+    /// struct literals take their struct from it
+    fn check_value(self: &mut Self, expected: &TypeSignature, node: &Node) {
+        let found = match node.token {
+            TokenTypes::StructLiteral if !self.is_named(node) => {
+                self.struct_literal(expected, node);
+                return;
+            }
+            TokenTypes::VecLiteral => self.vec_literal(expected, node),
+            _ => self.expression(node),
+        };
+        self.expect(expected, &found, node);
+    }
+
+    /// The items take their type from the expected one, or from the first
+    /// item when it is not known
+    fn vec_literal(self: &mut Self, expected: &TypeSignature, node: &Node) -> TypeSignature {
+        if self.is_map(expected) {
+            return self.map_literal(expected, node);
+        }
+        let mut item = match expected.data_type {
+            DataTypes::Vec => expected.generics.first().cloned().unwrap_or_default(),
+            _ => TypeSignature::default(),
+        };
+        for child in self.children(node) {
+            if item.is_unknown() {
+                item = self.expression(child);
+            } else {
+                self.check_value(&item, child);
+            }
+        }
+        let mut found = TypeSignature::new(DataTypes::Vec);
+        found.generics.push(item);
+        self.analysis.types.insert(node.id, found.clone());
+        return found;
+    }
+
+    /// Keys and values take turns, `["name", "Nebulang", "type", "scripted"]`
+    fn map_literal(self: &mut Self, expected: &TypeSignature, node: &Node) -> TypeSignature {
+        let key = expected.generics.first().cloned().unwrap_or_default();
+        let value = expected.generics.get(1).cloned().unwrap_or_default();
+        let items = self.children(node);
+        for (index, item) in items.iter().enumerate() {
+            match index % 2 {
+                0 => self.check_value(&key, item),
+                _ => self.check_value(&value, item),
+            }
+        }
+        if !items.len().is_multiple_of(2) {
+            self.diagnostics.push(Diagnostic::at_node(
+                "Every key of a map literal needs a value".to_string(),
+                node,
+            ).with_note("keys and values take turns, `[\"name\", \"Nebulang\"]`".to_string()));
+        }
+        self.analysis.types.insert(node.id, expected.clone());
+        return expected.clone();
+    }
+
+    /// Name of the runtime type the natives of the value are named after,
+    /// `vec` for `push^vec`
+    fn builtin(self: &Self, signature: &TypeSignature) -> Option<&'static str> {
+        match signature.data_type {
+            DataTypes::Vec => Some("vec"),
+            DataTypes::Str => Some("str"),
+            _ if self.is_map(signature) => Some(MAP),
+            _ => None,
+        }
+    }
+
+    /// Whether the type is the `map` of std
+    fn is_map(self: &Self, signature: &TypeSignature) -> bool {
+        matches!(&signature.data_type, DataTypes::User(name) if name == MAP)
+            && self.builtins.iter().any(|builtin| builtin == MAP)
+    }
+
+    /// Whether the struct literal names its struct, `conveyor<t> { ... }`
+    fn is_named(self: &Self, node: &Node) -> bool {
+        self.children(node)
+            .first()
+            .is_some_and(|child| matches!(child.token, TokenTypes::Type(_)))
+    }
+
+    /// The struct named in front of the literal, unknown when it does not
+    /// exist
+    fn named_struct(self: &mut Self, node: &Node) -> Option<TypeSignature> {
+        let written = self.children(node)
+            .into_iter()
+            .next()
+            .filter(|child| matches!(child.token, TokenTypes::Type(_)))?;
+        let named = self.resolve(written);
+        match self.validate(&named, written) {
+            true => Some(named),
+            false => Some(TypeSignature::default()),
+        }
+    }
+
+    fn struct_literal(self: &mut Self, expected: &TypeSignature, node: &Node) {
+        let declared = match &expected.data_type {
+            DataTypes::User(name) => self.structs.get(name).cloned(),
+            DataTypes::Unknown => None,
+            _ => {
+                self.diagnostics.push(Diagnostic::at_node(
+                    format!("Mismatched types, expected `{}`, found a struct literal", expected),
+                    node,
+                ));
+                None
+            }
+        };
+        let declared = match declared {
+            Some(declared) => declared,
+            // Still look for mistakes in the values
+            None => {
+                for field in self.children(node) {
+                    for value in self.children(field) {
+                        self.expression(value);
+                    }
+                }
+                return;
+            }
+        };
+        self.analysis.types.insert(node.id, expected.clone());
+        // Values of structs extending another one know their kind
+        if let (DataTypes::User(name), Some(TypeSignature { data_type: DataTypes::User(_), .. })) =
+            (&expected.data_type, &declared.extends) {
+            let kind = match &declared.import {
+                Some(module) => format!("{}@{}", name, module),
+                None => name.clone(),
+            };
+            self.analysis.made.insert(node.id, kind);
+        }
+        let mut set: Vec<&str> = vec![];
+        for field in self.children(node) {
+            let name = match &field.token {
+                TokenTypes::Field(name) => name.as_str(),
+                _ => continue,
+            };
+            let value = self.children(field).first().copied();
+            let index = declared.fields.iter().position(|(existing, _)| existing == name);
+            match index {
+                Some(index) if !set.contains(&name) => {
+                    set.push(name);
+                    self.analysis.fields.insert(field.id, index as u32);
+                    if let Some(value) = value {
+                        self.check_value(&declared.field(index, expected), value);
+                    }
+                }
+                Some(_) => {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        format!("Field `{}` is already set", name),
+                        field,
+                    ));
+                }
+                None => {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        format!("`{}` has no field `{}`", expected, name),
+                        field,
+                    ));
+                }
+            }
+        }
+        let missing: Vec<String> = declared.fields
+            .iter()
+            .filter(|(name, _)| !set.contains(&name.as_str()))
+            .map(|(name, _)| format!("`{}`", name))
+            .collect();
+        if !missing.is_empty() {
+            self.diagnostics.push(Diagnostic::at_node(
+                format!("Missing fields {} in `{}`", missing.join(", "), expected),
+                node,
+            ));
+        }
+    }
+
+    /// Reports the structs used in the type that do not exist, returning
+    /// whether the type is valid
+    fn validate(self: &mut Self, signature: &TypeSignature, node: &Node) -> bool {
+        let message = match &signature.data_type {
+            DataTypes::User(name) if self.type_params.contains(name) => None,
+            DataTypes::User(name) if self.builtins.contains(name) => {
+                let key = signature.generics.first();
+                let hashable = key.is_none_or(|key| match &key.data_type {
+                    DataTypes::Int
+                    | DataTypes::Bol
+                    | DataTypes::Chr
+                    | DataTypes::Str
+                    | DataTypes::Unknown => true,
+                    DataTypes::User(param) => self.type_params.contains(param),
+                    _ => false,
+                });
+                if signature.generics.len() != 2 {
+                    Some(format!(
+                        "`{}` expects 2 type parameters, found {}",
+                        name, signature.generics.len()
+                    ))
+                } else if !hashable {
+                    Some(format!("Maps cannot have keys of type `{}`", key.cloned().unwrap_or_default()))
+                } else {
+                    None
+                }
+            }
+            DataTypes::User(name) => match self.structs.get(name) {
+                Some(declared) if declared.generics.len() != signature.generics.len() => {
+                    Some(format!(
+                        "`{}` expects {} type parameters, found {}",
+                        name, declared.generics.len(), signature.generics.len()
+                    ))
+                }
+                Some(_) => None,
+                None if name == MAP => {
+                    self.diagnostics.push(Diagnostic::at_node(
+                        format!("Cannot find type `{}`", name),
+                        node,
+                    ).with_note("it is in the std library, `^map@std`".to_string()));
+                    return false;
+                }
+                None => Some(format!("Cannot find type `{}`", name)),
+            },
+            // Only left when there is no struct to replace it with
+            DataTypes::Slf => {
+                self.diagnostics.push(Diagnostic::at_node(
+                    "`self` can only be used in functions of a struct".to_string(),
+                    node,
+                ).with_note("they are declared with `#name^struct`".to_string()));
+                return false;
+            }
+            _ => None,
+        };
+        if let Some(message) = message {
+            self.diagnostics.push(Diagnostic::at_node(message, node));
+            return false;
+        }
+        let mut valid = true;
+        for generic in &signature.generics {
+            valid &= self.validate(generic, node);
+        }
+        return valid;
+    }
+
+    /// Reports a mismatch unless `found` can be used where `expected` is.
+    /// `int` values can be used as `flt` and structs as the ones they extend.
+    fn expect(self: &mut Self, expected: &TypeSignature, found: &TypeSignature, node: &Node) {
+        if matches(expected, found) {
+            return;
+        }
+        if expected.data_type == DataTypes::Flt && found.data_type == DataTypes::Int {
+            self.widen(node);
+            return;
+        }
+        if let DataTypes::User(name) = &expected.data_type {
+            if self.upcast(found, name).is_some_and(|upcast| matches(expected, &upcast)) {
+                return;
+            }
+        }
+        if expected.data_type == DataTypes::Vec {
+            if let Some((_, field)) = self.items(found).filter(|(items, _)| matches(expected, items)) {
+                self.analysis.items.insert(node.id, field);
+                return;
+            }
+        }
+        let mut diagnostic = Diagnostic::at_node(
+            format!("Mismatched types, expected `{}`, found `{}`", expected, found),
+            node,
+        );
+        if found.data_type == DataTypes::Void {
+            diagnostic = diagnostic.with_note("this does not return a value".to_string());
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Turns an int expression into a flt one, down to the operands of its
+    /// arithmetic so `flt half = 7 / 2` divides flts
+    fn widen(self: &mut Self, node: &Node) {
+        match &node.token {
+            TokenTypes::Group
+            | TokenTypes::Unary(_)
+            | TokenTypes::Binary(
+                Operators::Add | Operators::Sub | Operators::Mul | Operators::Div | Operators::Mod
+            ) => {
+                for child in self.children(node) {
+                    self.widen(child);
+                }
+                self.analysis.types.insert(node.id, TypeSignature::new(DataTypes::Flt));
+            }
+            _ => {
+                self.analysis.widened.insert(node.id);
+            }
+        }
+    }
+
+    /// Adds the variable declared by a `Type` node to the scope
+    fn declare(self: &mut Self, node: &Node) {
+        let mut declared = self.resolve(node);
+        if !self.validate(&declared, node) {
+            declared = TypeSignature::default();
+        }
+        self.declare_as(node, declared);
+    }
+
+    fn declare_as(self: &mut Self, node: &Node, declared: TypeSignature) {
+        for child in self.children(node) {
+            let variable = match &child.token {
+                TokenTypes::Variable(variable) => variable,
+                _ => continue,
+            };
+            self.analysis.types.insert(child.id, declared.clone());
+            if let Err(previous) = self.scope.declare(variable.clone(), child.id) {
+                let previous = &self.nodes[previous as usize];
+                self.diagnostics.push(Diagnostic::at_node(
+                    format!("`{}` is already declared in this block", variable),
+                    child,
+                ).with_note(format!(
+                    "first declared at {}:{}, inner blocks can shadow it instead",
+                    previous.cursor.line, previous.cursor.column
+                )));
+            }
+        }
+    }
+
+    /// Binds the use of a variable to its declaration, returning its type
+    fn lookup(self: &mut Self, variable: &str, node: &Node) -> TypeSignature {
+        match self.scope.lookup(variable) {
+            Some(declaration) => {
+                self.analysis.bindings.insert(node.id, declaration);
+                match self.analysis.types.get(&declaration) {
+                    Some(declared) => declared.clone(),
+                    None => TypeSignature::default(),
+                }
+            }
+            None => {
+                self.diagnostics.push(Diagnostic::at_node(
+                    format!("Cannot find `{}` in this scope", variable),
+                    node,
+                ));
+                TypeSignature::default()
+            }
+        }
+    }
+
+    /// The type written in a `Type` node, with its generics
+    fn resolve(self: &Self, node: &Node) -> TypeSignature {
+        let data_type = match &node.token {
+            TokenTypes::Type(DataTypes::Slf) if self.implementing.is_some() => {
+                return self.implementing.clone().unwrap_or_default();
+            }
+            TokenTypes::Type(data_type) => data_type.clone(),
+            _ => return TypeSignature::default(),
+        };
+        let mut signature = TypeSignature::new(data_type);
+        for child in self.children(node) {
+            if child.token == TokenTypes::Generic {
+                // `map<str><int>` is the same as `map<str, int>`
+                let generics: Vec<TypeSignature> = self.children(child)
+                    .into_iter()
+                    .map(|generic| self.resolve(generic))
+                    .collect();
+                signature.generics.extend(generics);
+            }
+        }
+        return signature;
+    }
+
+    fn children(self: &Self, node: &Node) -> Vec<&'a Node> {
+        node.children
+            .iter()
+            .filter_map(|id| self.nodes.get(*id as usize))
+            .collect()
+    }
+}
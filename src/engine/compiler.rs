@@ -1,37 +1,667 @@
+use std::fmt::Display;
 use std::fs::File;
 use std::io::prelude::*;
 
+use hashbrown::HashMap;
+
+use super::bytecode::{Casts, Function, Kind, OpCodes, Program};
+use super::checker::{declarations, function_name, import_path, MAP};
+use super::loader::Module;
+use super::types::{Cursor, DataTypes, Node, Operators, TokenTypes};
 use super::{parser::Parser, config::Config};
 
 pub struct Compiler;
 
+#[derive(Debug)]
+pub enum CompileError {
+    UnknownFunction(String),
+    UnknownVariable(String),
+    UnknownStruct(String),
+    /// Calls and functions take at most `u8::MAX` arguments
+    TooManyArguments(String),
+    Unsupported(TokenTypes),
+    Io(String),
+}
+
+impl Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownFunction(name) => write!(f, "Unknown function {}", name),
+            Self::UnknownVariable(name) => write!(f, "Unknown variable {}", name),
+            Self::UnknownStruct(name) => write!(f, "Unknown struct {}", name),
+            Self::TooManyArguments(name) => {
+                write!(f, "#{} cannot take more than {} arguments", name, u8::MAX)
+            }
+            Self::Unsupported(token) => write!(f, "Cannot compile {} yet", token),
+            Self::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// State kept while emitting the code of a single function
+struct FunctionEmitter {
+    function: Function,
+    /// Module the function is in, see `Module::name`
+    module: Option<String>,
+    /// Slot of every variable, by the id of the node that declared it
+    locals: HashMap<u64, u32>,
+    /// Jumps of the breaks in each loop being emitted, patched to the end
+    /// of the loop once it is known
+    breaks: Vec<Vec<usize>>,
+}
+
+impl FunctionEmitter {
+    fn new(name: String, module: Option<String>) -> Self {
+        Self {
+            function: Function::new(compiled_name(&name, module.as_deref()), 0),
+            module,
+            locals: HashMap::new(),
+            breaks: vec![],
+        }
+    }
+    fn declare(self: &mut Self, declaration: &Node) -> u32 {
+        let slot = self.function.locals;
+        self.locals.insert(declaration.id, slot);
+        self.function.locals += 1;
+        return slot;
+    }
+    /// A slot that no variable uses, for values needed more than once
+    fn temporary(self: &mut Self) -> u32 {
+        self.function.locals += 1;
+        return self.function.locals - 1;
+    }
+    /// Slot of the variable used by the node, as bound by the checker
+    fn local(self: &Self, parser: &Parser, node: &Node) -> Result<u32, CompileError> {
+        parser.analysis.bindings
+            .get(&node.id)
+            .and_then(|declaration| self.locals.get(declaration))
+            .copied()
+            .ok_or_else(|| CompileError::UnknownVariable(match &node.token {
+                TokenTypes::Variable(variable) => variable.clone(),
+                token => token.to_string(),
+            }))
+    }
+    fn emit(self: &mut Self, op: OpCodes, cursor: Cursor) {
+        self.function.emit(op, cursor);
+    }
+}
+
+/// Name of a function of the module in the program, imported functions
+/// already have theirs, see `Module::name`
+pub fn compiled_name(name: &str, module: Option<&str>) -> String {
+    match module {
+        Some(module) if !name.contains('@') => format!("{}@{}", name, module),
+        _ => name.to_string(),
+    }
+}
+
 impl Compiler {
-    pub fn compile(parser: &Parser, config: &Config) {
-        let file = File::create(config.out.clone());
-        match file {
-            Ok(mut file) => {
-                    // println!("{}", token);
-                file.write(format!("{:#?}\n", parser.symbols).as_bytes())
-                    .expect(
-                        format!("Could not write to file: {}", config.out.clone())
-                        .as_str()
-                    );
-
-                file.write("\n".as_bytes())
-                    .expect(
-                        format!("Could not write to file: {}", config.out.clone())
-                        .as_str()
-                    );
-                
-                file.write(format!("{:#?}\n", parser.nodes).as_bytes())
-                    .expect(
-                        format!("Could not write to file: {}", config.out.clone())
-                        .as_str()
-                    );
-            },
-            Err(error) => {
-                println!("Unable to open file {} {}", config.out.clone(), error);
-            },
-        }
-    }
-}
\ No newline at end of file
+    pub fn compile(modules: &[Module], config: &Config) -> Result<Program, CompileError> {
+        let program = Self::emit_modules(modules)?;
+        let mut file = File::create(config.out.clone())
+            .map_err(|error| CompileError::Io(
+                format!("Unable to open file {} {}", config.out, error)
+            ))?;
+        file.write_all(&program.to_bytes())
+            .map_err(|error| CompileError::Io(
+                format!("Could not write to file: {} {}", config.out, error)
+            ))?;
+        Ok(program)
+    }
+
+    /// Walks the node tree of the parser and generates the bytecode for
+    /// every function in it
+    pub fn emit(parser: &Parser) -> Result<Program, CompileError> {
+        let mut program = Program::default();
+        Self::emit_module(parser, None, &mut program)?;
+        program.entry = program.function_index("main");
+        Ok(program)
+    }
+
+    /// Generates the bytecode of every module into a single program, the
+    /// last module is the one that was run
+    pub fn emit_modules(modules: &[Module]) -> Result<Program, CompileError> {
+        let mut program = Program::default();
+        for module in modules {
+            Self::emit_module(&module.parser, module.name.as_deref(), &mut program)?;
+        }
+        program.entry = program.function_index("main");
+        Ok(program)
+    }
+
+    fn emit_module(
+        parser: &Parser,
+        module: Option<&str>,
+        program: &mut Program,
+    ) -> Result<(), CompileError> {
+        let nodes = &parser.nodes;
+        // Functions are registered first so calls can refer to functions
+        // that are declared later in the file
+        let functions: Vec<&Node> = declarations(nodes)
+            .into_iter()
+            .filter(|node| matches!(node.token, TokenTypes::Function(_)))
+            .filter(|node| import_path(nodes, node).is_none())
+            .collect();
+        let first = program.functions.len();
+        for node in &functions {
+            if let Some(name) = function_name(nodes, node) {
+                program.functions.push(Function::new(compiled_name(&name, module), 0));
+            }
+        }
+        // Methods of the kinds can be functions of the module or of the ones
+        // it imports
+        for (name, methods) in &parser.analysis.kinds {
+            let mut kind = Kind {
+                name: compiled_name(name, module),
+                methods: vec![],
+            };
+            for (method, called) in methods {
+                let compiled = compiled_name(called, module);
+                let function = program.function_index(&compiled)
+                    .ok_or(CompileError::UnknownFunction(compiled))?;
+                kind.methods.push((program.intern(method), function));
+            }
+            program.kinds.push(kind);
+        }
+        for (index, node) in functions.iter().enumerate() {
+            let function = Self::emit_function(parser, program, module, node)?;
+            program.functions[first + index] = function;
+        }
+        Ok(())
+    }
+
+    fn emit_function(
+        parser: &Parser,
+        program: &mut Program,
+        module: Option<&str>,
+        node: &Node,
+    ) -> Result<Function, CompileError> {
+        let name = function_name(&parser.nodes, node)
+            .ok_or_else(|| CompileError::Unsupported(node.token.clone()))?;
+        let mut emitter = FunctionEmitter::new(name, module.map(str::to_string));
+        for child in Self::children(parser, node) {
+            match &child.token {
+                TokenTypes::Params => {
+                    for param in Self::children(parser, child) {
+                        if let Some(variable) = Self::declared_variable(parser, param) {
+                            emitter.declare(variable);
+                            emitter.function.params = emitter.function.params
+                                .checked_add(1)
+                                .ok_or_else(|| CompileError::TooManyArguments(emitter.function.name.clone()))?;
+                        }
+                    }
+                }
+                TokenTypes::Body => {
+                    Self::emit_body(parser, program, &mut emitter, child)?;
+                }
+                TokenTypes::ShortReturn => {
+                    Self::emit_return(parser, program, &mut emitter, child)?;
+                }
+                _ => {}
+            }
+        }
+        // Functions that do not return anything still leave a value behind
+        emitter.emit(OpCodes::PushVoid, node.cursor);
+        emitter.emit(OpCodes::Return, node.cursor);
+        Ok(emitter.function)
+    }
+
+    fn emit_body(
+        parser: &Parser,
+        program: &mut Program,
+        emitter: &mut FunctionEmitter,
+        node: &Node,
+    ) -> Result<(), CompileError> {
+        for child in Self::children(parser, node) {
+            match &child.token {
+                TokenTypes::Statement => {
+                    Self::emit_statement(parser, program, emitter, child)?;
+                }
+                TokenTypes::Return => {
+                    Self::emit_return(parser, program, emitter, child)?;
+                }
+                TokenTypes::If => {
+                    Self::emit_if(parser, program, emitter, child)?;
+                }
+                TokenTypes::Loop => {
+                    Self::emit_loop(parser, program, emitter, child)?;
+                }
+                TokenTypes::Break => {
+                    let jump = emitter.function.emit(OpCodes::Jump(0), child.cursor);
+                    match emitter.breaks.last_mut() {
+                        Some(breaks) => breaks.push(jump),
+                        None => return Err(CompileError::Unsupported(child.token.clone())),
+                    }
+                }
+                TokenTypes::Call(_) => {
+                    Self::emit_call(parser, program, emitter, child)?;
+                    // The result of a call on its own is not used
+                    emitter.emit(OpCodes::Pop, child.cursor);
+                }
+                token => return Err(CompileError::Unsupported(token.clone())),
+            }
+        }
+        Ok(())
+    }
+
+    /// The body is jumped over when the condition is false
+    fn emit_if(
+        parser: &Parser,
+        program: &mut Program,
+        emitter: &mut FunctionEmitter,
+        node: &Node,
+    ) -> Result<(), CompileError> {
+        let mut jump = None;
+        for child in Self::children(parser, node) {
+            match &child.token {
+                TokenTypes::Condition => {
+                    for condition in Self::children(parser, child) {
+                        Self::emit_expression(parser, program, emitter, condition)?;
+                    }
+                    jump = Some(emitter.function.emit(OpCodes::JumpIfFalse(0), child.cursor));
+                }
+                TokenTypes::Body => {
+                    Self::emit_body(parser, program, emitter, child)?;
+                }
+                token => return Err(CompileError::Unsupported(token.clone())),
+            }
+        }
+        if let Some(jump) = jump {
+            let end = emitter.function.code.len() as u32;
+            emitter.function.code[jump] = OpCodes::JumpIfFalse(end);
+        }
+        Ok(())
+    }
+
+    /// Loops jump back to their start until a break jumps past them
+    fn emit_loop(
+        parser: &Parser,
+        program: &mut Program,
+        emitter: &mut FunctionEmitter,
+        node: &Node,
+    ) -> Result<(), CompileError> {
+        let start = emitter.function.code.len() as u32;
+        emitter.breaks.push(vec![]);
+        for child in Self::children(parser, node) {
+            Self::emit_body(parser, program, emitter, child)?;
+        }
+        emitter.emit(OpCodes::Jump(start), node.cursor);
+        let end = emitter.function.code.len() as u32;
+        for jump in emitter.breaks.pop().unwrap_or_default() {
+            emitter.function.code[jump] = OpCodes::Jump(end);
+        }
+        Ok(())
+    }
+
+    fn emit_return(
+        parser: &Parser,
+        program: &mut Program,
+        emitter: &mut FunctionEmitter,
+        node: &Node,
+    ) -> Result<(), CompileError> {
+        let expression = Self::children(parser, node)
+            .into_iter()
+            .flat_map(|statement| Self::children(parser, statement))
+            .next();
+        match expression {
+            Some(expression) => {
+                Self::emit_expression(parser, program, emitter, expression)?;
+            }
+            None => {
+                emitter.emit(OpCodes::PushVoid, node.cursor);
+            }
+        }
+        emitter.emit(OpCodes::Return, node.cursor);
+        Ok(())
+    }
+
+    fn emit_statement(
+        parser: &Parser,
+        program: &mut Program,
+        emitter: &mut FunctionEmitter,
+        node: &Node,
+    ) -> Result<(), CompileError> {
+        let children = Self::children(parser, node);
+        let operator = children.get(1).and_then(|node| match &node.token {
+            TokenTypes::Operator(operator) => Some(operator.clone()),
+            _ => None,
+        });
+        let value = children.get(2).copied();
+        let target = match children.first() {
+            Some(target) => *target,
+            None => return Ok(()),
+        };
+        match (&target.token, operator) {
+            // Declaration, `int a = 1`
+            (TokenTypes::Type(data_type), operator) => {
+                let variable = Self::declared_variable(parser, target)
+                    .ok_or(CompileError::Unsupported(target.token.clone()))?;
+                match (operator, value) {
+                    (Some(Operators::Ass), Some(value)) => {
+                        Self::emit_expression(parser, program, emitter, value)?;
+                    }
+                    _ => Self::emit_default(program, emitter, data_type, target.cursor),
+                }
+                let slot = emitter.declare(variable);
+                emitter.emit(OpCodes::Store(slot), target.cursor);
+            }
+            // Reassignment, `a = 1`, `a += 1`, `a++`
+            (TokenTypes::Variable(_), Some(operator)) => {
+                let slot = emitter.local(parser, target)?;
+                let cursor = target.cursor;
+                match (&operator, value) {
+                    (Operators::Ass, Some(value)) => {
+                        Self::emit_expression(parser, program, emitter, value)?;
+                    }
+                    (Operators::AddAdd | Operators::SubSub, None) => {
+                        emitter.emit(OpCodes::Load(slot), cursor);
+                        emitter.emit(OpCodes::PushInt(1), cursor);
+                        emitter.emit(match operator {
+                            Operators::AddAdd => OpCodes::Add,
+                            _ => OpCodes::Sub,
+                        }, cursor);
+                    }
+                    (Operators::AssAdd | Operators::AssSub, Some(value)) => {
+                        emitter.emit(OpCodes::Load(slot), cursor);
+                        Self::emit_expression(parser, program, emitter, value)?;
+                        emitter.emit(match operator {
+                            Operators::AssAdd => OpCodes::Add,
+                            _ => OpCodes::Sub,
+                        }, cursor);
+                    }
+                    _ => return Err(CompileError::Unsupported(TokenTypes::Operator(operator))),
+                }
+                emitter.emit(OpCodes::Store(slot), cursor);
+            }
+            // Anything else is an expression whose value is not used
+            _ => {
+                Self::emit_expression(parser, program, emitter, target)?;
+                emitter.emit(OpCodes::Pop, node.cursor);
+            }
+        }
+        Ok(())
+    }
+
+    fn emit_expression(
+        parser: &Parser,
+        program: &mut Program,
+        emitter: &mut FunctionEmitter,
+        node: &Node,
+    ) -> Result<(), CompileError> {
+        let is_widened = parser.analysis.widened.contains(&node.id);
+        match &node.token {
+            // Ints used as flts are written as flts
+            TokenTypes::LiteralInt(int) if is_widened => {
+                emitter.emit(OpCodes::PushFlt(*int as f64), node.cursor);
+                return Ok(());
+            }
+            TokenTypes::LiteralInt(int) => {
+                emitter.emit(OpCodes::PushInt(*int), node.cursor);
+            }
+            TokenTypes::LiteralFloat(flt) => {
+                emitter.emit(OpCodes::PushFlt(*flt), node.cursor);
+            }
+            TokenTypes::LiteralChar(chr) => {
+                emitter.emit(OpCodes::PushChr(*chr), node.cursor);
+            }
+            TokenTypes::LiteralBool(bol) => {
+                emitter.emit(OpCodes::PushBol(*bol), node.cursor);
+            }
+            TokenTypes::LiteralString(string) => {
+                let index = program.intern(string);
+                emitter.emit(OpCodes::PushStr(index), node.cursor);
+            }
+            TokenTypes::Variable(_) => {
+                let slot = emitter.local(parser, node)?;
+                emitter.emit(OpCodes::Load(slot), node.cursor);
+            }
+            // Indexes of structs call their `get` function
+            TokenTypes::Index if !parser.analysis.methods.contains_key(&node.id) => {
+                for child in Self::children(parser, node) {
+                    Self::emit_expression(parser, program, emitter, child)?;
+                }
+                emitter.emit(OpCodes::GetIndex, node.cursor);
+            }
+            TokenTypes::Call(_)
+            | TokenTypes::MethodCall(_)
+            | TokenTypes::Index => {
+                Self::emit_call(parser, program, emitter, node)?;
+            }
+            // `list.length` is read like a field but runs in the runtime
+            TokenTypes::Access(_) if parser.analysis.methods.contains_key(&node.id) => {
+                Self::emit_call(parser, program, emitter, node)?;
+            }
+            TokenTypes::VecLiteral => {
+                let items = Self::children(parser, node);
+                for item in &items {
+                    Self::emit_expression(parser, program, emitter, item)?;
+                }
+                // Keys and values of maps are written like the items of vecs
+                let is_map = parser.analysis.types
+                    .get(&node.id)
+                    .is_some_and(|found| found.data_type == DataTypes::User(MAP.to_string()));
+                let op = match is_map {
+                    true => OpCodes::MakeMap(items.len() as u32 / 2),
+                    false => OpCodes::MakeVec(items.len() as u32),
+                };
+                emitter.emit(op, node.cursor);
+            }
+            // Casts to the type the value already has do nothing
+            TokenTypes::Cast => {
+                let value = Self::children(parser, node)
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| CompileError::Unsupported(node.token.clone()))?;
+                Self::emit_expression(parser, program, emitter, value)?;
+                let found = parser.analysis.types.get(&value.id).map(|found| &found.data_type);
+                let target = parser.analysis.types.get(&node.id).map(|target| &target.data_type);
+                if let Some(cast) = target.and_then(Casts::new).filter(|_| found != target) {
+                    emitter.emit(OpCodes::Cast(cast), node.cursor);
+                }
+            }
+            TokenTypes::Group => {
+                for child in Self::children(parser, node) {
+                    Self::emit_expression(parser, program, emitter, child)?;
+                }
+            }
+            TokenTypes::Unary(operator) => {
+                for child in Self::children(parser, node) {
+                    Self::emit_expression(parser, program, emitter, child)?;
+                }
+                match operator {
+                    Operators::Sub => emitter.emit(OpCodes::Neg, node.cursor),
+                    _ => return Err(CompileError::Unsupported(node.token.clone())),
+                }
+            }
+            TokenTypes::Binary(operator) => {
+                for child in Self::children(parser, node) {
+                    Self::emit_expression(parser, program, emitter, child)?;
+                }
+                Self::emit_operator(emitter, operator, node)?;
+            }
+            TokenTypes::StructLiteral => {
+                // Fields can be set in any order but are stored in the
+                // order of the declaration
+                let mut fields: Vec<(u32, &Node)> = vec![];
+                for field in Self::children(parser, node) {
+                    // The struct named in front of the literal
+                    if let TokenTypes::Type(_) = field.token {
+                        continue;
+                    }
+                    let index = Self::field_index(parser, field)?;
+                    fields.push((index, field));
+                }
+                fields.sort_by_key(|(index, _)| *index);
+                for (_, field) in &fields {
+                    for value in Self::children(parser, field) {
+                        Self::emit_expression(parser, program, emitter, value)?;
+                    }
+                }
+                emitter.emit(OpCodes::MakeStruct(fields.len() as u32), node.cursor);
+                if let Some(kind) = parser.analysis.made.get(&node.id) {
+                    let compiled = compiled_name(kind, emitter.module.as_deref());
+                    let kind = program.kind_index(&compiled)
+                        .ok_or(CompileError::UnknownStruct(compiled))?;
+                    emitter.emit(OpCodes::SetKind(kind), node.cursor);
+                }
+            }
+            TokenTypes::Access(field) => {
+                for child in Self::children(parser, node) {
+                    Self::emit_expression(parser, program, emitter, child)?;
+                }
+                // Only keys of maps are read without being a field
+                let op = match parser.analysis.fields.get(&node.id) {
+                    Some(index) => OpCodes::GetField(*index),
+                    None => OpCodes::GetKey(program.intern(field)),
+                };
+                emitter.emit(op, node.cursor);
+            }
+            token => return Err(CompileError::Unsupported(token.clone())),
+        }
+        if is_widened {
+            emitter.emit(OpCodes::Cast(Casts::Flt), node.cursor);
+        }
+        // Structs extending `vec` used as their vec
+        if let Some(field) = parser.analysis.items.get(&node.id) {
+            emitter.emit(OpCodes::GetField(*field), node.cursor);
+        }
+        Ok(())
+    }
+
+    fn emit_operator(
+        emitter: &mut FunctionEmitter,
+        operator: &Operators,
+        node: &Node,
+    ) -> Result<(), CompileError> {
+        let op = match operator {
+            Operators::Mul      => OpCodes::Mul,
+            Operators::Add      => OpCodes::Add,
+            Operators::Sub      => OpCodes::Sub,
+            Operators::Div      => OpCodes::Div,
+            Operators::Mod      => OpCodes::Mod,
+            Operators::Lt       => OpCodes::Lt,
+            Operators::Gt       => OpCodes::Gt,
+            Operators::BitOr    => OpCodes::BitOr,
+            Operators::BitAnd   => OpCodes::BitAnd,
+            Operators::Equ      => OpCodes::Equ,
+            Operators::NotEqu   => OpCodes::NotEqu,
+            Operators::EquGt    => OpCodes::EquGt,
+            Operators::EquLt    => OpCodes::EquLt,
+            Operators::BitLeft  => OpCodes::BitLeft,
+            Operators::BitRight => OpCodes::BitRight,
+            _ => return Err(CompileError::Unsupported(node.token.clone())),
+        };
+        emitter.emit(op, node.cursor);
+        Ok(())
+    }
+
+    fn emit_call(
+        parser: &Parser,
+        program: &mut Program,
+        emitter: &mut FunctionEmitter,
+        node: &Node,
+    ) -> Result<(), CompileError> {
+        let name = match &node.token {
+            // Functions inherited from an extended struct are resolved too
+            TokenTypes::Call(name) => parser.analysis.methods
+                .get(&node.id)
+                .unwrap_or(name)
+                .clone(),
+            // Resolved by the checker from the type of the receiver
+            TokenTypes::MethodCall(method) => parser.analysis.methods
+                .get(&node.id)
+                .cloned()
+                .ok_or_else(|| CompileError::UnknownFunction(method.clone()))?,
+            TokenTypes::Index
+            | TokenTypes::Access(_) => parser.analysis.methods
+                .get(&node.id)
+                .cloned()
+                .ok_or_else(|| CompileError::Unsupported(node.token.clone()))?,
+            token => return Err(CompileError::Unsupported(token.clone())),
+        };
+        let rewrapped = parser.analysis.rewrapped.get(&node.id).copied();
+        let mut args: u8 = 0;
+        for child in Self::children(parser, node) {
+            if child.token != TokenTypes::CallParams {
+                // The receiver of a method call or what is indexed and the
+                // index
+                Self::emit_expression(parser, program, emitter, child)?;
+                // The struct stays under its items to take the new ones
+                if let Some(field) = rewrapped {
+                    let slot = emitter.temporary();
+                    emitter.emit(OpCodes::Store(slot), child.cursor);
+                    emitter.emit(OpCodes::Load(slot), child.cursor);
+                    emitter.emit(OpCodes::Load(slot), child.cursor);
+                    emitter.emit(OpCodes::GetField(field), child.cursor);
+                }
+                args = args.checked_add(1).ok_or_else(|| CompileError::TooManyArguments(name.clone()))?;
+                continue;
+            }
+            for argument in Self::children(parser, child) {
+                Self::emit_expression(parser, program, emitter, argument)?;
+                args = args.checked_add(1).ok_or_else(|| CompileError::TooManyArguments(name.clone()))?;
+            }
+        }
+        let compiled = compiled_name(&name, emitter.module.as_deref());
+        if let Some(index) = program.function_index(&compiled) {
+            // Structs extending the one of the receiver can replace it
+            let op = match name.split_once('^') {
+                Some((method, _)) if parser.analysis.dispatched.contains(&node.id) => {
+                    OpCodes::CallMethod(program.intern(method), index, args)
+                }
+                _ => OpCodes::Call(index, args),
+            };
+            emitter.emit(op, node.cursor);
+        } else if parser.natives.contains(&name) {
+            let index = program.intern(&name);
+            emitter.emit(OpCodes::CallNative(index, args), node.cursor);
+        } else {
+            return Err(CompileError::UnknownFunction(name));
+        }
+        if let Some(field) = rewrapped {
+            emitter.emit(OpCodes::SetField(field), node.cursor);
+        }
+        Ok(())
+    }
+
+    fn emit_default(
+        program: &mut Program,
+        emitter: &mut FunctionEmitter,
+        data_type: &DataTypes,
+        cursor: Cursor,
+    ) {
+        let op = match data_type {
+            DataTypes::Int => OpCodes::PushInt(0),
+            DataTypes::Flt => OpCodes::PushFlt(0.0),
+            DataTypes::Bol => OpCodes::PushBol(false),
+            DataTypes::Chr => OpCodes::PushChr('\0'),
+            DataTypes::Str => OpCodes::PushStr(program.intern("")),
+            DataTypes::Vec => OpCodes::MakeVec(0),
+            DataTypes::User(name) if name == MAP => OpCodes::MakeMap(0),
+            _ => OpCodes::PushVoid,
+        };
+        emitter.emit(op, cursor);
+    }
+
+    /// Position of the field in its struct, as found by the checker
+    fn field_index(parser: &Parser, node: &Node) -> Result<u32, CompileError> {
+        parser.analysis.fields
+            .get(&node.id)
+            .copied()
+            .ok_or_else(|| CompileError::Unsupported(node.token.clone()))
+    }
+
+    /// The variable declared by a `Type` node, `int a` declares `a`
+    fn declared_variable<'a>(parser: &'a Parser, node: &Node) -> Option<&'a Node> {
+        Self::children(parser, node)
+            .into_iter()
+            .find(|child| matches!(child.token, TokenTypes::Variable(_)))
+    }
+
+    fn children<'a>(parser: &'a Parser, node: &Node) -> Vec<&'a Node> {
+        node.children
+            .iter()
+            .filter_map(|id| parser.nodes.get(*id as usize))
+            .collect()
+    }
+}
@@ -1,6 +1,6 @@
 // use std::fs;
 // use std::env;
-// use std::path::Path;
+use std::path::Path;
 
 use super::util::get_index;
 
@@ -14,12 +14,54 @@ pub struct ConfigFile {
 
 #[derive(Debug, Clone)]
 pub struct Config {
+    pub command: Commands,
     pub src: String,
     pub out: String,
     pub origin: String,
     pub debbuging: bool,
     pub low_mem: bool,
     pub string_delimiter: char,
+    /// Arguments after `--`, passed to the #main function
+    pub args: Vec<String>,
+}
+
+impl Config {
+    /// Checks the source with every flag left out, it names the source in
+    /// diagnostics and the files it imports are looked for next to it
+    pub fn new(src: &str) -> Self {
+        Self {
+            command: Commands::Check,
+            src: src.to_string(),
+            out: String::new(),
+            origin: origin(src),
+            debbuging: false,
+            low_mem: false,
+            string_delimiter: '"',
+            args: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Commands {
+    /// Parse, compile and execute the source file
+    Run,
+    /// Compile the source file into a ByteCode file
+    Build,
+    /// Only parse and compile the source file to report errors
+    Check,
+}
+
+impl TryFrom<&str> for Commands {
+    type Error = String;
+    fn try_from(command: &str) -> Result<Self, Self::Error> {
+        match command {
+            "run" => Ok(Self::Run),
+            "build" => Ok(Self::Build),
+            "check" => Ok(Self::Check),
+            _ => Err(format!("Unknown command {}", command)),
+        }
+    }
 }
 
 pub fn extract_config(args: &Vec<String>) -> Result<Config, String> {
@@ -39,6 +81,11 @@ pub fn extract_config(args: &Vec<String>) -> Result<Config, String> {
     //     }
     // }
 
+    // nebulang <command> <src> [flags] [-- args...]
+    if let Some(command) = args.get(1).filter(|arg| !arg.starts_with("--")) {
+        return extract_command(Commands::try_from(command.as_str())?, &args[2..]);
+    }
+
     // Check if instead, the arguments were passed
     let has_src         = args.contains(&"--src".to_string());
     let has_out         = args.contains(&"--out".to_string());
@@ -56,12 +103,14 @@ pub fn extract_config(args: &Vec<String>) -> Result<Config, String> {
             "\"".to_string()
         };
         return Ok(Config {
+            command: Commands::Build,
+            origin: origin(&src),
             src,
             out,
-            origin: ".".to_string(),
             debbuging: false,
             low_mem: has_low_mem,
             string_delimiter: del.pop().expect("--qq passed but no argument"),
+            args: vec![],
         });
     }
 
@@ -78,6 +127,72 @@ pub fn extract_config(args: &Vec<String>) -> Result<Config, String> {
     return Err("No configuration provided!".to_string());
 }
 
+fn extract_command(command: Commands, args: &[String]) -> Result<Config, String> {
+    let mut src: Option<String> = None;
+    let mut out: Option<String> = None;
+    let mut debbuging = false;
+    let mut low_mem = false;
+    let mut string_delimiter = '"';
+    let mut program_args = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => {
+                program_args = args.cloned().collect();
+                break;
+            }
+            "--out" => {
+                out = Some(args.next()
+                    .ok_or("--out passed but no path")?
+                    .to_owned());
+            }
+            "--qq" => {
+                string_delimiter = args.next()
+                    .and_then(|del| del.chars().last())
+                    .ok_or("--qq passed but no argument")?;
+            }
+            "--low-mem" => low_mem = true,
+            "--debug" => debbuging = true,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown flag {}", flag));
+            }
+            path => {
+                if src.is_some() {
+                    return Err(format!("Unexpected argument {}", path));
+                }
+                src = Some(path.to_owned());
+            }
+        }
+    }
+    let src = src.ok_or("No source file given")?;
+    // Imports are found from the directory of the source file
+    let origin = origin(&src);
+    // Build next to the source file unless told otherwise
+    let out = out.unwrap_or_else(|| Path::new(&src)
+        .with_extension("nbc")
+        .to_string_lossy()
+        .to_string()
+    );
+    return Ok(Config {
+        command,
+        src,
+        out,
+        origin,
+        debbuging,
+        low_mem,
+        string_delimiter,
+        args: program_args,
+    });
+}
+
+/// Directory of the source file
+fn origin(src: &str) -> String {
+    match Path::new(src).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().to_string(),
+        _ => ".".to_string(),
+    }
+}
+
 // fn load_config(path: &str) -> Result<Config, String> {
 //     let contents = fs::read_to_string(path);
 //     let json;
@@ -1,5 +1,348 @@
-pub fn register() -> Vec<(String, String)> {
-    vec![
-        ("print".to_string(), "print".to_string()),
-    ]
-}
\ No newline at end of file
+use std::fmt::{self, Debug, Formatter};
+use std::rc::Rc;
+
+use hashbrown::HashMap;
+
+use super::checker::{substitute, MAP};
+use super::types::{DataTypes, TypeSignature};
+use super::vm::{Key, Value};
+
+/// Implementation of a native function, it gets the values it was called
+/// with and checks them itself
+pub type NativeFunction = Rc<dyn Fn(&[Value]) -> Result<Value, String>>;
+
+/// A function of the runtime written in Rust
+#[derive(Clone)]
+pub struct Native {
+    /// Type parameters of the parameters, `t` in `vec<t>`
+    pub generics: Vec<String>,
+    /// `None` when it takes any values, `print`
+    pub params: Option<Vec<TypeSignature>>,
+    pub returns: TypeSignature,
+    pub function: NativeFunction,
+}
+
+impl Native {
+    pub fn new<F>(params: Vec<TypeSignature>, returns: TypeSignature, function: F) -> Self
+    where
+        F: Fn(&[Value]) -> Result<Value, String> + 'static,
+    {
+        Self {
+            generics: vec![],
+            params: Some(params),
+            returns,
+            function: Rc::new(function),
+        }
+    }
+
+    /// Takes any amount of values of any type
+    pub fn variadic<F>(returns: TypeSignature, function: F) -> Self
+    where
+        F: Fn(&[Value]) -> Result<Value, String> + 'static,
+    {
+        Self {
+            generics: vec![],
+            params: None,
+            returns,
+            function: Rc::new(function),
+        }
+    }
+
+    pub fn with_generics(mut self, generics: &[&str]) -> Self {
+        self.generics = generics.iter().map(|generic| generic.to_string()).collect();
+        self
+    }
+}
+
+/// Fields of a struct as the host sees them, in the order they were declared
+#[derive(Debug, Default, Clone)]
+pub struct Fields {
+    /// Type parameters of the struct, `t` in `conveyor<t>`
+    pub generics: Vec<String>,
+    pub types: Vec<TypeSignature>,
+}
+
+/// Fields of the structs a file can use, by the name it uses them with
+pub type Structs = HashMap<String, Fields>;
+
+/// Whether a value of the host can be given where the type is expected, the
+/// type parameters in `generics` take any value
+pub fn fits(value: &Value, expected: &TypeSignature, generics: &[String], structs: &Structs) -> bool {
+    // Items, keys and values have the type of the generic at their position
+    let all_fit = |values: Vec<Value>, index: usize| match expected.generics.get(index) {
+        Some(generic) => values.iter().all(|value| fits(value, generic, generics, structs)),
+        None => true,
+    };
+    match (&expected.data_type, value) {
+        (DataTypes::Unknown, _) => true,
+        (DataTypes::User(name), _) if generics.contains(name) => true,
+        (DataTypes::Void, Value::Void)
+        | (DataTypes::Int, Value::Int(_))
+        | (DataTypes::Flt, Value::Flt(_))
+        | (DataTypes::Bol, Value::Bol(_))
+        | (DataTypes::Chr, Value::Chr(_))
+        | (DataTypes::Str, Value::Str(_)) => true,
+        (DataTypes::Vec, Value::Vec(items)) => all_fit(items.to_vec(), 0),
+        (DataTypes::User(name), Value::Map(map)) if name == MAP => {
+            all_fit(map.keys().iter().map(|key| key.value()).collect(), 0)
+                && all_fit(map.keys().iter().filter_map(|key| map.get(key).cloned()).collect(), 1)
+        }
+        (DataTypes::User(name), Value::Struct(values, kind)) => match structs.get(name) {
+            Some(fields) => {
+                // Values of a struct extending it start with its fields
+                let counted = match kind {
+                    Some(_) => values.len() >= fields.types.len(),
+                    None => values.len() == fields.types.len(),
+                };
+                let bindings = fields.generics
+                    .iter()
+                    .cloned()
+                    .zip(expected.generics.iter().cloned())
+                    .collect();
+                counted && fields.types.iter().zip(values.iter()).all(|(field, value)| {
+                    fits(value, &substitute(field, &fields.generics, &bindings), generics, structs)
+                })
+            }
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/// Native functions by the name they are called with, the functions of a
+/// type are named like the ones of a struct, `push^vec`
+#[derive(Clone, Default)]
+pub struct Natives {
+    functions: HashMap<String, Native>,
+    /// Structs of the host the functions can return
+    structs: Structs,
+}
+
+impl Natives {
+    /// Fails when the name is taken
+    pub fn register(self: &mut Self, name: &str, native: Native) -> Result<(), String> {
+        if self.functions.contains_key(name) {
+            return Err(format!("The native function `{}` is already registered", name));
+        }
+        self.functions.insert(name.to_string(), native);
+        Ok(())
+    }
+
+    /// Fields of a struct of the host, the values the functions return are
+    /// checked against them
+    pub fn register_struct(self: &mut Self, name: &str, fields: Fields) {
+        self.structs.insert(name.to_string(), fields);
+    }
+
+    pub fn get(self: &Self, name: &str) -> Option<&Native> {
+        self.functions.get(name)
+    }
+
+    pub fn contains(self: &Self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    /// Runs the native function registered as `name`, failing when it does
+    /// not return the type it was registered with
+    pub fn call(self: &Self, name: &str, args: &[Value]) -> Result<Value, String> {
+        let native = match self.functions.get(name) {
+            Some(native) => native,
+            None => return Err(format!("Unknown native function {}", name)),
+        };
+        let value = (native.function)(args)?;
+        if !fits(&value, &native.returns, &native.generics, &self.structs) {
+            return Err(format!(
+                "Native function `{}` returned a {} instead of a value of type `{}`",
+                name, value.type_name(), native.returns
+            ));
+        }
+        Ok(value)
+    }
+}
+
+impl Debug for Natives {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut names: Vec<&String> = self.functions.keys().collect();
+        names.sort();
+        f.debug_set().entries(names).finish()
+    }
+}
+
+fn basic(data_type: DataTypes) -> TypeSignature {
+    TypeSignature::new(data_type)
+}
+
+/// A type parameter of the function
+fn param(name: &str) -> TypeSignature {
+    TypeSignature::new(DataTypes::User(name.to_string()))
+}
+
+fn vec_of(item: TypeSignature) -> TypeSignature {
+    TypeSignature {
+        data_type: DataTypes::Vec,
+        generics: vec![item],
+    }
+}
+
+fn map_of(key: TypeSignature, value: TypeSignature) -> TypeSignature {
+    TypeSignature {
+        data_type: DataTypes::User(MAP.to_string()),
+        generics: vec![key, value],
+    }
+}
+
+/// The functions that come with the runtime
+pub fn register() -> Natives {
+    register_with(|line| println!("{}", line))
+}
+
+/// The functions that come with the runtime, `print` hands its lines to
+/// `print` instead of writing them to stdout
+pub fn register_with<P>(print: P) -> Natives
+where
+    P: Fn(&str) + 'static,
+{
+    let t = || param("t");
+    let k = || param("k");
+    let v = || param("v");
+    let natives: Vec<(&str, Native)> = vec![
+        ("print", Native::variadic(TypeSignature::void(), move |args| {
+            print(&format_args("print", args)?);
+            Ok(Value::Void)
+        })),
+        ("format", Native::variadic(basic(DataTypes::Str), |args| {
+            Ok(Value::Str(format_args("format", args)?.into()))
+        })),
+        // Vecs are never changed in place, a new one is returned instead
+        ("push^vec", Native::new(vec![vec_of(t()), t()], vec_of(t()), |args| match args {
+            [Value::Vec(items), item] => {
+                let mut items = items.as_ref().clone();
+                items.push(item.clone());
+                Ok(Value::Vec(Rc::new(items)))
+            }
+            _ => Err(mismatch("push^vec", args)),
+        }).with_generics(&["t"])),
+        // Without its first item, `conveyor` drops the oldest one with it
+        ("unshift^vec", Native::new(vec![vec_of(t())], vec_of(t()), |args| match args {
+            [Value::Vec(items)] => {
+                Ok(Value::Vec(Rc::new(items.iter().skip(1).cloned().collect())))
+            }
+            _ => Err(mismatch("unshift^vec", args)),
+        }).with_generics(&["t"])),
+        ("join^vec", Native::new(
+            vec![vec_of(t()), basic(DataTypes::Str)],
+            basic(DataTypes::Str),
+            |args| match args {
+                [Value::Vec(items), Value::Str(separator)] => {
+                    let joined = items
+                        .iter()
+                        .map(|item| item.to_string())
+                        .collect::<Vec<String>>()
+                        .join(separator);
+                    Ok(Value::Str(joined.into()))
+                }
+                _ => Err(mismatch("join^vec", args)),
+            },
+        ).with_generics(&["t"])),
+        ("length^vec", Native::new(vec![vec_of(t())], basic(DataTypes::Int), |args| match args {
+            [Value::Vec(items)] => Ok(Value::Int(items.len() as i64)),
+            _ => Err(mismatch("length^vec", args)),
+        }).with_generics(&["t"])),
+        ("length^str", Native::new(vec![basic(DataTypes::Str)], basic(DataTypes::Int), |args| match args {
+            [Value::Str(string)] => Ok(Value::Int(string.chars().count() as i64)),
+            _ => Err(mismatch("length^str", args)),
+        })),
+        // Functions of `map`, it is imported from std
+        ("get^map", Native::new(vec![map_of(k(), v()), k()], v(), |args| match args {
+            [Value::Map(map), key] => map
+                .get(&Key::new(key)?)
+                .cloned()
+                .ok_or_else(|| format!("Map has no key `{}`", key)),
+            _ => Err(mismatch("get^map", args)),
+        }).with_generics(&["k", "v"])),
+        ("set^map", Native::new(vec![map_of(k(), v()), k(), v()], map_of(k(), v()), |args| match args {
+            [Value::Map(map), key, value] => {
+                let mut map = map.as_ref().clone();
+                map.set(Key::new(key)?, value.clone());
+                Ok(Value::Map(Rc::new(map)))
+            }
+            _ => Err(mismatch("set^map", args)),
+        }).with_generics(&["k", "v"])),
+        ("has^map", Native::new(vec![map_of(k(), v()), k()], basic(DataTypes::Bol), |args| match args {
+            [Value::Map(map), key] => Ok(Value::Bol(map.get(&Key::new(key)?).is_some())),
+            _ => Err(mismatch("has^map", args)),
+        }).with_generics(&["k", "v"])),
+        ("remove^map", Native::new(vec![map_of(k(), v()), k()], map_of(k(), v()), |args| match args {
+            [Value::Map(map), key] => {
+                let mut map = map.as_ref().clone();
+                map.remove(&Key::new(key)?);
+                Ok(Value::Map(Rc::new(map)))
+            }
+            _ => Err(mismatch("remove^map", args)),
+        }).with_generics(&["k", "v"])),
+        ("keys^map", Native::new(vec![map_of(k(), v())], vec_of(k()), |args| match args {
+            [Value::Map(map)] => {
+                Ok(Value::Vec(Rc::new(map.keys().iter().map(Key::value).collect())))
+            }
+            _ => Err(mismatch("keys^map", args)),
+        }).with_generics(&["k", "v"])),
+    ];
+    let mut registered = Natives::default();
+    for (name, native) in natives {
+        registered.register(name, native).expect("Could not register core functions");
+    }
+    registered
+}
+
+/// The text between the `{}` placeholders of a format string, `{{` and `}}`
+/// are the braces themselves
+pub fn format_pieces(format: &str) -> Result<Vec<String>, String> {
+    let mut pieces = vec![String::new()];
+    let mut chars = format.chars().peekable();
+    while let Some(character) = chars.next() {
+        let piece = pieces.last_mut().unwrap();
+        match character {
+            '{' | '}' if chars.next_if_eq(&character).is_some() => piece.push(character),
+            '{' if chars.next_if_eq(&'}').is_some() => pieces.push(String::new()),
+            '{' => return Err("Expected `}` right after `{` in the format".to_string()),
+            '}' => return Err("Unmatched `}` in the format".to_string()),
+            _ => piece.push(character),
+        }
+    }
+    Ok(pieces)
+}
+
+/// Replaces each `{}` of the format with the next value
+pub fn format(format: &str, values: &[Value]) -> Result<String, String> {
+    let pieces = format_pieces(format)?;
+    if pieces.len() - 1 != values.len() {
+        return Err(format!(
+            "Expected {} values for the placeholders of the format, found {}",
+            pieces.len() - 1, values.len()
+        ));
+    }
+    let mut formatted = String::new();
+    for (index, piece) in pieces.iter().enumerate() {
+        formatted.push_str(piece);
+        if let Some(value) = values.get(index) {
+            formatted.push_str(&value.to_string());
+        }
+    }
+    Ok(formatted)
+}
+
+/// `print` and `format` take a format and its values, a single value that
+/// is not a str is used as it is
+fn format_args(name: &str, args: &[Value]) -> Result<String, String> {
+    match args {
+        [] => Ok(String::new()),
+        [Value::Str(string), values @ ..] => format(string, values),
+        [value] => Ok(value.to_string()),
+        _ => Err(mismatch(name, args)),
+    }
+}
+
+fn mismatch(name: &str, args: &[Value]) -> String {
+    let types: Vec<&str> = args.iter().map(|arg| arg.type_name()).collect();
+    format!("#{} cannot take ({})", name, types.join(", "))
+}
@@ -0,0 +1,120 @@
+use std::fmt::Display;
+
+use super::types::{Cursor, Node, SourceId, Symbol};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        })
+    }
+}
+
+/// A message about a place in the source code
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    pub start: Cursor,
+    pub end: Cursor,
+    pub notes: Vec<String>,
+    /// File or buffer the diagnostic is about
+    pub source: SourceId,
+}
+
+impl Diagnostic {
+    pub fn new(message: String, start: Cursor, end: Cursor) -> Self {
+        Self {
+            level: Level::Error,
+            message,
+            start,
+            end,
+            notes: vec![],
+            source: SourceId::default(),
+        }
+    }
+    pub fn error(message: String, symbol: &Symbol) -> Self {
+        Self::new(message, symbol.start, symbol.end).in_source(&symbol.source)
+    }
+    pub fn at_node(message: String, node: &Node) -> Self {
+        let len = node.len.max(1) as u64;
+        let end = Cursor {
+            pos: node.cursor.pos + len - 1,
+            line: node.cursor.line,
+            column: node.cursor.column + len - 1,
+        };
+        Self::new(message, node.cursor, end)
+    }
+    pub fn with_note(mut self: Self, note: String) -> Self {
+        self.notes.push(note);
+        return self;
+    }
+    pub fn in_source(mut self: Self, source: &SourceId) -> Self {
+        self.source = source.clone();
+        return self;
+    }
+    pub fn warning(mut self: Self) -> Self {
+        self.level = Level::Warning;
+        return self;
+    }
+
+    /// Renders the diagnostic like rustc does, with the line of the source
+    /// and the offending symbol underlined
+    /// ```text
+    /// error: Cannot find `b` in this scope
+    ///  --> main.nl:3:13
+    ///   |
+    /// 3 |     int a = b;
+    ///   |             ^
+    /// ```
+    pub fn render(self: &Self, source: &str) -> String {
+        let line_number = self.start.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let mut out = format!(
+            "{}: {}\n{}--> {}:{}:{}\n",
+            self.level, self.message, gutter, self.source, self.start.line, self.start.column
+        );
+        if let Some(line) = source.lines().nth((self.start.line as usize).saturating_sub(1)) {
+            // Spans over several lines are only underlined in the first one
+            let line_len = line.chars().count() as u64;
+            let start = self.start.column.max(1);
+            let end = if self.end.line == self.start.line {
+                self.end.column.max(start)
+            } else {
+                line_len.max(start)
+            };
+            let padding: String = line
+                .chars()
+                .take((start - 1) as usize)
+                // Keep tabs so the caret lines up with the source
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            out += &format!("{} |\n", gutter);
+            out += &format!("{} | {}\n", line_number, line);
+            out += &format!(
+                "{} | {}{}\n",
+                gutter, padding, "^".repeat((end - start + 1) as usize)
+            );
+        }
+        for note in &self.notes {
+            out += &format!("{} = note: {}\n", gutter, note);
+        }
+        return out;
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "{}: {} at {}:{}",
+            self.level, self.message, self.start.line, self.start.column
+        )
+    }
+}
@@ -0,0 +1,426 @@
+use super::diagnostic::Diagnostic;
+use super::types::{method_name, DataTypes, Node, Operators, Symbol, TokenTypes};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Associativity {
+    Left,
+    Right,
+    /// `a == b == c` is an error instead of being grouped
+    None,
+}
+
+/// Binding power of unary operators, above every binary operator
+const UNARY_PRECEDENCE: u8 = 8;
+
+impl Operators {
+    /// Precedence and associativity of the operator when used between two
+    /// operands, the higher it is the tighter it binds. Mirrors Rust.
+    pub fn precedence(self: &Self) -> (u8, Associativity) {
+        match self {
+            Operators::Mul
+            | Operators::Div
+            | Operators::Mod => (7, Associativity::Left),
+            Operators::Add
+            | Operators::Sub => (6, Associativity::Left),
+            Operators::BitLeft
+            | Operators::BitRight => (5, Associativity::Left),
+            Operators::BitAnd => (4, Associativity::Left),
+            Operators::BitOr => (3, Associativity::Left),
+            Operators::Equ
+            | Operators::NotEqu
+            | Operators::Lt
+            | Operators::Gt
+            | Operators::EquGt
+            | Operators::EquLt => (2, Associativity::None),
+            // Assignments are split from the expression by the statement,
+            // they only reach here when misused
+            Operators::Ass
+            | Operators::AssAdd
+            | Operators::AssSub => (1, Associativity::Right),
+            // Postfix, only valid as a statement of their own
+            Operators::AddAdd
+            | Operators::SubSub => (UNARY_PRECEDENCE, Associativity::Left),
+        }
+    }
+    pub fn is_assignment(self: &Self) -> bool {
+        matches!(self, Operators::Ass | Operators::AssAdd | Operators::AssSub)
+    }
+    pub fn is_increment(self: &Self) -> bool {
+        matches!(self, Operators::AddAdd | Operators::SubSub)
+    }
+}
+
+/// Turns the flat children of an expression container (statements,
+/// conditions, groups, call parameters, vec items and struct literal fields)
+/// into a tree of `Binary`, `Unary` and `Access` nodes
+pub fn build(nodes: &mut Vec<Node>, container: u64, symbols: &[Symbol]) -> Result<(), Diagnostic> {
+    let node = nodes[container as usize].clone();
+    let children = node.children.clone();
+    let new_children = match node.token {
+        TokenTypes::Statement => {
+            let start = statement_expression_start(nodes, &children);
+            let mut new_children = children[..start].to_vec();
+            if start < children.len() {
+                new_children.push(parse(nodes, &children[start..], symbols)?);
+            } else if start > 0 && is_assignment_node(&nodes[children[start - 1] as usize]) {
+                let operator = &nodes[children[start - 1] as usize];
+                return Err(Diagnostic::at_node(
+                    "Expected an expression after the assignment".to_string(),
+                    operator,
+                ));
+            }
+            new_children
+        }
+        TokenTypes::Condition
+        | TokenTypes::Group
+        | TokenTypes::Index
+        | TokenTypes::Field(_) => {
+            if children.is_empty() {
+                return Err(Diagnostic::at_node(
+                    "Expected an expression".to_string(),
+                    &node,
+                ));
+            }
+            vec![parse(nodes, &children, symbols)?]
+        }
+        TokenTypes::CallParams
+        | TokenTypes::VecLiteral => {
+            if children.is_empty() {
+                return Ok(());
+            }
+            let mut separated: Vec<Vec<u64>> = children
+                .split(|id| nodes[*id as usize].token == TokenTypes::Separator)
+                .map(|argument| argument.to_vec())
+                .collect();
+            // Items on lines of their own can all end with a comma
+            if node.token == TokenTypes::VecLiteral
+                && separated.len() > 1
+                && separated.last().is_some_and(|item| item.is_empty()) {
+                separated.pop();
+            }
+            let mut arguments = vec![];
+            for argument in separated {
+                if argument.is_empty() {
+                    return Err(Diagnostic::at_node(
+                        "Expected an argument".to_string(),
+                        &node,
+                    ));
+                }
+                arguments.push(parse(nodes, &argument, symbols)?);
+            }
+            arguments
+        }
+        _ => return Ok(()),
+    };
+    for child in &new_children {
+        nodes[*child as usize].parent = container;
+    }
+    nodes[container as usize].children = new_children;
+    Ok(())
+}
+
+/// Statements start with what is being assigned, `int a =`, `a +=`, those
+/// are kept as they are and the rest is the expression
+fn statement_expression_start(nodes: &[Node], children: &[u64]) -> usize {
+    let token = |index: usize| children
+        .get(index)
+        .map(|id| &nodes[*id as usize].token);
+    match (token(0), token(1)) {
+        (Some(TokenTypes::Type(_)), Some(TokenTypes::Operator(operator)))
+        | (Some(TokenTypes::Variable(_)), Some(TokenTypes::Operator(operator)))
+            if operator.is_assignment() => 2,
+        // `int a;`
+        (Some(TokenTypes::Type(_)), None) => 1,
+        // `a++;`
+        (Some(TokenTypes::Variable(_)), Some(TokenTypes::Operator(operator)))
+            if operator.is_increment() && children.len() == 2 => 2,
+        _ => 0,
+    }
+}
+
+fn is_assignment_node(node: &Node) -> bool {
+    match &node.token {
+        TokenTypes::Operator(operator) => operator.is_assignment(),
+        _ => false,
+    }
+}
+
+fn parse(nodes: &mut Vec<Node>, ids: &[u64], symbols: &[Symbol]) -> Result<u64, Diagnostic> {
+    let mut parser = ExpressionParser {
+        nodes,
+        ids,
+        pos: 0,
+    };
+    let root = parser.expression(0)?;
+    if let Some(id) = parser.peek() {
+        let node = &parser.nodes[id as usize];
+        // Shown the way it is written, `"a"` rather than the token
+        let written = symbols
+            .iter()
+            .find(|symbol| symbol.start.pos == node.cursor.pos)
+            .map(|symbol| symbol.symbol.clone())
+            .unwrap_or_else(|| node.token.to_string());
+        return Err(Diagnostic::at_node(
+            format!("Expected an operator, found `{}`", written),
+            node,
+        ));
+    }
+    Ok(root)
+}
+
+/// A type written inside of an expression, each generic is kept with the
+/// node of its `<`, `map<str><int>`
+struct WrittenType {
+    id: u64,
+    data_type: DataTypes,
+    generics: Vec<(u64, WrittenType)>,
+}
+
+/// Precedence climbing over nodes that were already created by the lexer
+struct ExpressionParser<'a> {
+    nodes: &'a mut Vec<Node>,
+    ids: &'a [u64],
+    pos: usize,
+}
+
+impl<'a> ExpressionParser<'a> {
+    fn peek(self: &Self) -> Option<u64> {
+        self.ids.get(self.pos).copied()
+    }
+
+    fn operator(self: &Self, id: u64) -> Option<Operators> {
+        match &self.nodes[id as usize].token {
+            TokenTypes::Operator(operator) => Some(operator.clone()),
+            _ => None,
+        }
+    }
+
+    fn expression(self: &mut Self, min_precedence: u8) -> Result<u64, Diagnostic> {
+        let mut left = self.unary()?;
+        let mut last_precedence: Option<u8> = None;
+        while let Some(id) = self.peek() {
+            let operator = match self.operator(id) {
+                Some(operator) => operator,
+                None => break,
+            };
+            let node = &self.nodes[id as usize];
+            // `person.name = ...`, `list[0] = ...`
+            let changed = match &self.nodes[left as usize].token {
+                TokenTypes::Access(_) => Some(("Fields", "structs")),
+                TokenTypes::Index => Some(("Items", "vecs and maps")),
+                _ => None,
+            };
+            if let Some((changed, values)) = changed.filter(|_| operator.is_assignment()) {
+                return Err(Diagnostic::at_node(
+                    format!("{} cannot be assigned, {} cannot be changed in place", changed, values),
+                    node,
+                ).with_note("reassign the variable with a new value instead".to_string()));
+            }
+            if operator.is_assignment() {
+                return Err(Diagnostic::at_node(
+                    format!("`{}` can only be used as a statement", operator.symbol()),
+                    node,
+                ).with_note("assignments do not have a value".to_string()));
+            }
+            if operator.is_increment() {
+                return Err(Diagnostic::at_node(
+                    format!("`{}` can only be used as a statement", operator.symbol()),
+                    node,
+                ));
+            }
+            let (precedence, associativity) = operator.precedence();
+            if precedence < min_precedence {
+                break;
+            }
+            if associativity == Associativity::None
+                && last_precedence == Some(precedence) {
+                return Err(Diagnostic::at_node(
+                    "Comparison operators cannot be chained".to_string(),
+                    node,
+                ).with_note("use parenthesis to group the comparisons".to_string()));
+            }
+            self.pos += 1;
+            let next_precedence = match associativity {
+                Associativity::Right => precedence,
+                _ => precedence + 1,
+            };
+            let right = self.expression(next_precedence)?;
+            self.link(id, TokenTypes::Binary(operator), vec![left, right]);
+            left = id;
+            last_precedence = Some(precedence);
+        }
+        Ok(left)
+    }
+
+    fn unary(self: &mut Self) -> Result<u64, Diagnostic> {
+        let id = match self.peek() {
+            Some(id) => id,
+            None => {
+                // Point at whatever came last
+                let last = self.ids.last().copied().unwrap_or_default();
+                return Err(Diagnostic::at_node(
+                    "Expected an expression".to_string(),
+                    &self.nodes[last as usize],
+                ));
+            }
+        };
+        self.pos += 1;
+        match self.operator(id) {
+            // Negation
+            Some(Operators::Sub) => {
+                let operand = self.expression(UNARY_PRECEDENCE)?;
+                self.link(id, TokenTypes::Unary(Operators::Sub), vec![operand]);
+                Ok(id)
+            }
+            // `<<` is only a return at the start of a statement
+            Some(Operators::BitLeft) => Err(Diagnostic::at_node(
+                "Expected an expression, found `<<`".to_string(),
+                &self.nodes[id as usize],
+            ).with_note("returning is only possible at the start of a statement".to_string())),
+            Some(operator) => Err(Diagnostic::at_node(
+                format!("Expected an expression, found `{}`", operator.symbol()),
+                &self.nodes[id as usize],
+            )),
+            None => match self.named_literal(id) {
+                Some(literal) => self.postfix(literal),
+                None => self.postfix(id),
+            },
+        }
+    }
+
+    /// `conveyor<t> { ... }`, the type in front of a struct literal becomes
+    /// its first child
+    fn named_literal(self: &mut Self, id: u64) -> Option<u64> {
+        let mut pos = self.pos - 1;
+        let mut pending = 0;
+        let written = self.written_type(&mut pos, &mut pending)?;
+        let literal = self.ids.get(pos).copied()?;
+        if pending > 0 || self.nodes[literal as usize].token != TokenTypes::StructLiteral {
+            return None;
+        }
+        self.pos = pos + 1;
+        self.link_type(&written);
+        let mut children = vec![id];
+        children.extend(self.nodes[literal as usize].children.clone());
+        self.link(literal, TokenTypes::StructLiteral, children);
+        Some(literal)
+    }
+
+    /// Reads a type written with operators, `vec<t>`. A `>>` closes two
+    /// groups, the one it does not close is left `pending`.
+    fn written_type(self: &Self, pos: &mut usize, pending: &mut u8) -> Option<WrittenType> {
+        let id = self.ids.get(*pos).copied()?;
+        let data_type = match &self.nodes[id as usize].token {
+            TokenTypes::Type(data_type) => data_type.clone(),
+            TokenTypes::Variable(name) => DataTypes::try_from(name.clone())
+                .unwrap_or_else(|_| DataTypes::User(name.clone())),
+            _ => return None,
+        };
+        *pos += 1;
+        let mut generics = vec![];
+        while *pending == 0 {
+            let open = match self.ids.get(*pos) {
+                Some(open) if self.operator(*open) == Some(Operators::Lt) => *open,
+                _ => break,
+            };
+            *pos += 1;
+            let generic = self.written_type(pos, pending)?;
+            if *pending > 0 {
+                *pending -= 1;
+            } else {
+                let close = self.ids.get(*pos).and_then(|close| self.operator(*close));
+                *pos += 1;
+                match close {
+                    Some(Operators::Gt) => {}
+                    Some(Operators::BitRight) => *pending += 1,
+                    _ => return None,
+                }
+            }
+            generics.push((open, generic));
+        }
+        Some(WrittenType { id, data_type, generics })
+    }
+
+    fn link_type(self: &mut Self, written: &WrittenType) {
+        let mut groups = vec![];
+        for (open, generic) in &written.generics {
+            self.link_type(generic);
+            self.link(*open, TokenTypes::Generic, vec![generic.id]);
+            groups.push(*open);
+        }
+        self.link(written.id, TokenTypes::Type(written.data_type.clone()), groups);
+    }
+
+    /// Field accesses, calls and casts bind tighter than any operator,
+    /// `-a.b` is `-(a.b)`
+    fn postfix(self: &mut Self, mut operand: u64) -> Result<u64, Diagnostic> {
+        match &self.nodes[operand as usize].token {
+            TokenTypes::Access(_) => return Err(Diagnostic::at_node(
+                "Expected an expression before the field".to_string(),
+                &self.nodes[operand as usize],
+            )),
+            TokenTypes::Static(_) => return Err(Diagnostic::at_node(
+                "Expected a struct before the function".to_string(),
+                &self.nodes[operand as usize],
+            )),
+            TokenTypes::Cast if self.nodes[operand as usize].children.len() < 2 => {
+                return Err(Diagnostic::at_node(
+                    "Expected a value before `~`".to_string(),
+                    &self.nodes[operand as usize],
+                ));
+            }
+            _ => {}
+        }
+        while let Some(id) = self.peek() {
+            let node = self.nodes[id as usize].clone();
+            match &node.token {
+                // `employee.nameplate()` has its parameters as a child
+                TokenTypes::Access(method) if !node.children.is_empty() => {
+                    let mut children = vec![operand];
+                    children.extend(node.children);
+                    self.link(id, TokenTypes::MethodCall(method.clone()), children);
+                }
+                TokenTypes::Access(_) => {
+                    self.link(id, node.token, vec![operand]);
+                }
+                TokenTypes::Index
+                | TokenTypes::Cast => {
+                    let mut children = vec![operand];
+                    children.extend(node.children);
+                    self.link(id, node.token, children);
+                }
+                // `person:new()` is a call to `new^person`
+                TokenTypes::Static(function) => {
+                    let structure = match &self.nodes[operand as usize].token {
+                        TokenTypes::Variable(structure) => structure.clone(),
+                        _ => return Err(Diagnostic::at_node(
+                            format!("Expected a struct before `:{}`", function),
+                            &self.nodes[operand as usize],
+                        )),
+                    };
+                    if node.children.is_empty() {
+                        return Err(Diagnostic::at_node(
+                            format!("Expected `(` after `{}:{}`", structure, function),
+                            &node,
+                        ));
+                    }
+                    let name = method_name(function, &structure);
+                    self.link(id, TokenTypes::Call(name), node.children);
+                }
+                _ => break,
+            }
+            self.pos += 1;
+            operand = id;
+        }
+        Ok(operand)
+    }
+
+    fn link(self: &mut Self, id: u64, token: TokenTypes, children: Vec<u64>) {
+        for child in &children {
+            self.nodes[*child as usize].parent = id;
+        }
+        let node = &mut self.nodes[id as usize];
+        node.token = token;
+        node.children = children;
+    }
+}
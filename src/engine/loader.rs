@@ -0,0 +1,253 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use super::checker::{declarations, import_path, Exports};
+use super::config::Config;
+use super::core::{self, Natives};
+use super::diagnostic::Diagnostic;
+use super::parser::Parser;
+use super::types::{Node, TokenTypes};
+
+/// Name of the library that comes with the language, `^map@std`
+pub const STD: &str = "std";
+
+/// A parsed and checked file of the program
+pub struct Module {
+    /// Functions of the module are compiled as `name@module`, the file that
+    /// was run has no name so its functions keep theirs
+    pub name: Option<String>,
+    pub path: PathBuf,
+    pub parser: Parser,
+}
+
+/// Errors of the file that could not be loaded
+#[derive(Debug)]
+pub struct LoadError {
+    pub path: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Loads the file given in the config and every file it imports, each one
+/// only once
+pub struct Loader {
+    config: Config,
+    /// Directory of the file that was run, modules are named after their
+    /// path from it. It is only looked up once a file is imported.
+    root: PathBuf,
+    modules: Vec<Module>,
+    /// Files being loaded, each one imports the next
+    loading: Vec<PathBuf>,
+    /// Functions of the runtime every module is checked with
+    natives: Natives,
+    /// Declarations every module can use without importing them
+    host: Exports,
+}
+
+impl Loader {
+    /// The modules of the program, the ones that are imported come before
+    /// the ones importing them and the file that was run is the last one
+    pub fn load(config: &Config) -> Result<Vec<Module>, LoadError> {
+        Self::new(config).load_file()
+    }
+
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: config.clone(),
+            root: PathBuf::from(&config.origin),
+            modules: vec![],
+            loading: vec![],
+            natives: core::register(),
+            host: Exports::default(),
+        }
+    }
+
+    pub fn with_natives(mut self: Self, natives: Natives) -> Self {
+        self.natives = natives;
+        return self;
+    }
+
+    pub fn with_host(mut self: Self, host: Exports) -> Self {
+        self.host = host;
+        return self;
+    }
+
+    /// Loads the file of the config, see `load`
+    pub fn load_file(mut self: Self) -> Result<Vec<Module>, LoadError> {
+        let path = PathBuf::from(&self.config.src);
+        self.load_module(&path, None)?;
+        Ok(self.modules)
+    }
+
+    /// Loads a program that is already in memory, it is named after the
+    /// file of the config and the files it imports are looked for next to it
+    pub fn load_source(mut self: Self, source: &str) -> Result<Vec<Module>, LoadError> {
+        let path = PathBuf::from(&self.config.src);
+        let mut parser = self.parser(&path);
+        parser.read_source(source).map_err(|diagnostics| load_error(&path, diagnostics))?;
+        self.add_module(&path, None, parser)?;
+        Ok(self.modules)
+    }
+
+    fn parser(self: &Self, path: &Path) -> Parser {
+        let mut config = self.config.clone();
+        config.src = path.to_string_lossy().to_string();
+        config.origin = path
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".".to_string());
+        let mut parser = Parser::new(config);
+        parser.natives = self.natives.clone();
+        return parser;
+    }
+
+    /// Index of the loaded module in `modules`
+    fn load_module(self: &mut Self, path: &Path, name: Option<String>) -> Result<usize, LoadError> {
+        let file = File::open(path).map_err(|_| load_error(path, vec![]))?;
+        let mut parser = self.parser(path);
+        parser.read(file).map_err(|diagnostics| load_error(path, diagnostics))?;
+        self.add_module(path, name, parser)
+    }
+
+    /// Loads what the parsed module imports and checks it
+    fn add_module(
+        self: &mut Self,
+        path: &Path,
+        name: Option<String>,
+        mut parser: Parser,
+    ) -> Result<usize, LoadError> {
+        let error = |diagnostics| load_error(path, diagnostics);
+        self.loading.push(path.to_path_buf());
+        let mut imports = self.host.clone();
+        let mut diagnostics = vec![];
+        for declaration in declarations(&parser.nodes) {
+            let import = match import_path(&parser.nodes, declaration) {
+                Some(import) => import,
+                None => continue,
+            };
+            match self.import(path, &import, declaration, &mut imports) {
+                Ok(Some(diagnostic)) => diagnostics.push(diagnostic.in_source(&parser.source())),
+                Ok(None) => {}
+                Err(error) => {
+                    self.loading.pop();
+                    return Err(error);
+                }
+            }
+        }
+        self.loading.pop();
+        if !diagnostics.is_empty() {
+            return Err(error(diagnostics));
+        }
+        parser.check(&imports).map_err(error)?;
+        self.modules.push(Module {
+            name,
+            path: path.to_path_buf(),
+            parser,
+        });
+        Ok(self.modules.len() - 1)
+    }
+
+    /// Adds what the declaration imports to `imports`, loading the file it
+    /// comes from if needed. Mistakes in the import itself are reported in
+    /// the importing file, errors in the imported file stop the loading.
+    fn import(
+        self: &mut Self,
+        importing: &Path,
+        import: &str,
+        declaration: &Node,
+        imports: &mut Exports,
+    ) -> Result<Option<Diagnostic>, LoadError> {
+        let imports_type = matches!(declaration.token, TokenTypes::Struct(_));
+        if import == STD && imports_type && Exports::import_std(declared_name(declaration), imports) {
+            return Ok(None);
+        }
+        if import == STD {
+            return Ok(Some(Diagnostic::at_node(
+                format!("Cannot find `{}` in the std library", declared_name(declaration)),
+                declaration,
+            )));
+        }
+        let directory = importing.parent().unwrap_or(Path::new("."));
+        let path = directory.join(import);
+        let canonical = match fs::canonicalize(&path) {
+            Ok(canonical) => canonical,
+            Err(_) => return Ok(Some(Diagnostic::at_node(
+                format!("Cannot find the file `{}`", import),
+                declaration,
+            ).with_note(format!("looked for it at {}", path.display())))),
+        };
+        if let Some(start) = self.loading.iter().position(|loading| resolved(loading) == canonical) {
+            let cycle: Vec<String> = self.loading[start..]
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|path| format!("`{}`", self.module_name(path)))
+                .collect();
+            return Ok(Some(Diagnostic::at_node(
+                format!("`{}` cannot be imported, it imports this file", import),
+                declaration,
+            ).with_note(format!("the files import each other: {}", cycle.join(" -> ")))));
+        }
+        let loaded = self.modules
+            .iter()
+            .position(|module| fs::canonicalize(&module.path).ok().as_ref() == Some(&canonical));
+        let index = match loaded {
+            Some(index) => index,
+            None => {
+                let name = self.module_name(&canonical);
+                self.load_module(&path, Some(name))?
+            }
+        };
+        let module = &self.modules[index];
+        let name = module.name.clone().unwrap_or_default();
+        let exports = &module.parser.analysis.exports;
+        let imported = match &declaration.token {
+            TokenTypes::Function(function) => exports.import_function(function, &name, imports),
+            TokenTypes::Struct(structure) => exports.import_struct(structure, &name, imports),
+            _ => false,
+        };
+        if imported {
+            return Ok(None);
+        }
+        let declared = declared_name(declaration);
+        let mut diagnostic = Diagnostic::at_node(
+            format!("`{}` does not export `{}`", import, declared),
+            declaration,
+        );
+        let exists = declarations(&module.parser.nodes)
+            .iter()
+            .any(|node| declared_name(node) == declared && node.token == declaration.token);
+        if exists {
+            diagnostic = diagnostic.with_note(
+                "it is declared there, export it with `<<` in front of it".to_string()
+            );
+        }
+        Ok(Some(diagnostic))
+    }
+
+    /// Path of the file from the directory of the file that was run
+    fn module_name(self: &Self, path: &Path) -> String {
+        let path = resolved(path);
+        path.strip_prefix(resolved(&self.root))
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+/// The path without links and `..`, or as it is when it cannot be found
+fn resolved(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn load_error(path: &Path, diagnostics: Vec<Diagnostic>) -> LoadError {
+    LoadError {
+        path: path.to_string_lossy().to_string(),
+        diagnostics,
+    }
+}
+
+fn declared_name(declaration: &Node) -> &str {
+    match &declaration.token {
+        TokenTypes::Function(name) | TokenTypes::Struct(name) => name,
+        _ => "",
+    }
+}
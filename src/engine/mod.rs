@@ -1,7 +1,14 @@
 pub mod core;
+pub mod bytecode;
+pub mod checker;
 pub mod compiler;
 pub mod config;
+pub mod diagnostic;
+pub mod expression;
+pub mod loader;
 pub mod parser;
+pub mod runtime;
 pub mod symbols;
 pub mod types;
-pub mod util;
\ No newline at end of file
+pub mod util;
+pub mod vm;
\ No newline at end of file
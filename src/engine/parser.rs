@@ -1,9 +1,11 @@
 use std::fmt::{Debug};
-use std::fs::File;
-
-use hashbrown::HashMap;
+use std::io::Read;
 
+use super::checker::{Analysis, Checker, Exports};
 use super::config::Config;
+use super::core::Natives;
+use super::diagnostic::Diagnostic;
+use super::expression;
 use super::util::{Conveyor, CharReader};
 use super::symbols::*;
 use super::types::*;
@@ -12,37 +14,34 @@ use super::types::*;
 pub struct Parser {
     pub nodes: Vec<Node>,
     pub symbols: Vec<Symbol>,
-    pub functions: HashMap<String, String>,
+    /// Functions of the runtime, the program can call them by name
+    pub natives: Natives,
+    pub analysis: Analysis,
     config: Config,
 }
 
-#[derive(Debug)]
-pub enum ParseError {
-    Syntax,
-    Arguments,
-    Unknown,
-    Expected,
-}
-
 #[derive(Debug)]
 pub struct SymbolState {
     is_in_string: bool,
     is_escaping: bool,
     is_in_comment: bool,
-    is_setting_string_delimiter: bool,
     could_be_double: Vec<char>,
+    /// Closes the string being read, it is the one that opened it
     current_string_delimiter: char,
+    /// Where the string being read started, for errors
+    string_start: Cursor,
 }
 
-impl Default for SymbolState {
-    fn default() -> Self {
+impl SymbolState {
+    /// Strings start out delimited by the global delimiter, `--qq`
+    pub fn new(string_delimiter: char) -> Self {
         Self {
             is_in_string:   false,
             is_escaping:    false,
             is_in_comment:  false,
-            is_setting_string_delimiter: false,
             could_be_double:vec![],
-            current_string_delimiter: '"',
+            current_string_delimiter: string_delimiter,
+            string_start: Cursor::default(),
         }
     }
 }
@@ -65,6 +64,11 @@ pub enum LexResult {
     New(Node),
     ChangeTo(u64),
     Up,
+    /// The next symbol was already used by this one
+    Skip,
+    /// Lex the same symbol again in the new working node
+    Retry,
+    Error(Diagnostic),
 }
 
 impl LexResult {
@@ -74,6 +78,9 @@ impl LexResult {
             Self::New(_) => "N",
             Self::ChangeTo(_) => "C",
             Self::Up => "U",
+            Self::Skip => "S",
+            Self::Retry => "R",
+            Self::Error(_) => "E",
         }.to_string()
     }
 }
@@ -83,31 +90,90 @@ impl Parser {
         Self {
             nodes:      vec![],
             symbols:    vec![],
-            functions:  HashMap::new(),
+            natives:    super::core::register(),
+            analysis:   Analysis::default(),
             config,
         }
     }
 
-    pub fn parse(self: &mut Self, file: File) -> Result<(), ParseError> {
-        let symbols = self.extract_symbols(file)
-            .expect("Could not parse symbols");
+    /// Parses and checks a source that does not import anything, it can be
+    /// a file or anything else that can be read
+    pub fn parse(self: &mut Self, reader: impl Read) -> Result<(), Vec<Diagnostic>> {
+        self.read(reader)?;
+        self.check(&Exports::default())
+    }
+
+    /// Same as `parse` with the source already in memory
+    pub fn parse_source(self: &mut Self, source: &str) -> Result<(), Vec<Diagnostic>> {
+        self.read_source(source)?;
+        self.check(&Exports::default())
+    }
+
+    /// Builds the node tree of the source, it is checked once the files it
+    /// imports are known
+    pub fn read(self: &mut Self, reader: impl Read) -> Result<(), Vec<Diagnostic>> {
+        let symbols = self.extract_symbols(reader)
+            .map_err(|error| vec![error])?;
+        self.build(symbols)
+    }
+
+    /// Same as `read` with the source already in memory
+    pub fn read_source(self: &mut Self, source: &str) -> Result<(), Vec<Diagnostic>> {
+        let symbols = self.symbols_of(source.chars())
+            .map_err(|error| vec![error])?;
+        self.build(symbols)
+    }
+
+    fn build(self: &mut Self, symbols: Vec<Symbol>) -> Result<(), Vec<Diagnostic>> {
         self.symbols = symbols.clone();
-        self.register_functions(super::core::register())
-            .expect("Could not register core functions");
         let nodes = self.tokenize_symbols(symbols)
-            .expect("Could not tokenize");
+            .map_err(|diagnostics| self.in_source(diagnostics))?;
         self.nodes = nodes;
         Ok(())
     }
 
+    pub fn check(self: &mut Self, imports: &Exports) -> Result<(), Vec<Diagnostic>> {
+        self.analysis = Checker::check(&self.nodes, &self.natives, imports)
+            .map_err(|diagnostics| self.in_source(diagnostics))?;
+        Ok(())
+    }
+
+    /// Name of the source being parsed, the file of the config
+    pub fn source(self: &Self) -> SourceId {
+        SourceId::from(self.config.src.as_str())
+    }
+
+    /// Diagnostics of the nodes do not know the source they are about
+    fn in_source(self: &Self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let source = self.source();
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.in_source(&source))
+            .collect()
+    }
+
     pub fn extract_symbols(
         self: &mut Self,
-        file: File
-    ) -> Result<Vec<Symbol>, ParseError> {
-        let mut reader = CharReader::new(file, Some(self.config.low_mem));
-        let mut char_buf: Conveyor<char> = Conveyor::new(64);
+        reader: impl Read,
+    ) -> Result<Vec<Symbol>, Diagnostic> {
+        let reader = CharReader::new(reader, Some(self.config.low_mem))
+            .map_err(|error| Diagnostic::new(
+                format!("Could not read {}: {}", self.config.src, error),
+                Cursor::default(),
+                Cursor::default(),
+            ).in_source(&self.source()))?;
+        self.symbols_of(reader)
+    }
+
+    /// Symbols of the source, each one knows the source it was read from
+    fn symbols_of(
+        self: &mut Self,
+        mut reader: impl Iterator<Item = char>,
+    ) -> Result<Vec<Symbol>, Diagnostic> {
+        let source = self.source();
+        let mut char_buf = String::new();
         let mut cursor = Cursor::default();
-        let mut symbol_state = SymbolState::default();
+        let mut symbol_state = SymbolState::new(self.config.string_delimiter);
         let mut symbols = Vec::new();
         while let Some(character) = reader.next() {
             char_buf.push(character);
@@ -118,7 +184,8 @@ impl Parser {
                 &cursor,
                 &mut symbol_state,
             ) {
-                for symbol in new_symbols {
+                for mut symbol in new_symbols {
+                    symbol.source = source.clone();
                     symbols.push(symbol);
                 }
             }
@@ -128,39 +195,54 @@ impl Parser {
             }
             cursor.column += 1;
         }
+        if symbol_state.is_in_string {
+            let start = symbol_state.string_start;
+            let literal = match symbol_state.current_string_delimiter {
+                CHR_DELIMITER => "char",
+                _ => "string",
+            };
+            return Err(Diagnostic::new(
+                format!("Unterminated {}", literal),
+                start,
+                start,
+            ).with_note(format!(
+                "expected the {} to be closed by {}",
+                literal, symbol_state.current_string_delimiter
+            )).in_source(&source));
+        }
         Ok(symbols)
     }
 
     pub fn read_symbol(
         self: &mut Self, 
         character: char,
-        char_buf: &mut Conveyor<char>,
+        char_buf: &mut String,
         cursor: &Cursor,
-        mut symbol_state: &mut SymbolState,
+        symbol_state: &mut SymbolState,
     ) -> Option<Vec<Symbol>> {
         let c_string = character.to_string();
         let c_str = c_string.as_str();
-        if character == '\\' {
-            symbol_state.is_escaping = true;
+        // Escapes are kept as they are and decoded with the rest of the
+        // string, the escaped character cannot close it
+        if symbol_state.is_in_string && symbol_state.is_escaping {
+            symbol_state.is_escaping = false;
             return None;
         }
-        if symbol_state.is_escaping {
-            symbol_state.is_escaping = false;
+        if symbol_state.is_in_string && character == '\\' {
+            symbol_state.is_escaping = true;
+            return None;
         }
+        // A `;` only ends the comment, the end of the line also ends the
+        // statement before it
         if symbol_state.is_in_comment {
-            if DELIMITERS.contains(&c_str) {
-                char_buf.clear();
-                symbol_state.is_in_comment = false;
+            if !DELIMITERS.contains(&c_str) {
+                return None;
+            }
+            char_buf.clear();
+            symbol_state.is_in_comment = false;
+            if c_str != "\n" {
+                return None;
             }
-            return None;
-        }
-        if symbol_state.is_setting_string_delimiter {
-            // Set the string delimiter after qq
-            symbol_state.current_string_delimiter = character;
-            symbol_state.is_setting_string_delimiter = false;
-            symbol_state.is_in_string = true;
-            // char_buf.clear();
-            return None;
         }
         if !symbol_state.could_be_double.is_empty() {
             let len = symbol_state.could_be_double.len();
@@ -174,17 +256,15 @@ impl Parser {
                     return None;
                 }
             }
-            // Check if it is changing the string delimiter
-            if STR_DELIMETER_DECLARATOR_DUO
-                .map(|v| v[len]).contains(&c_str) {
-                symbol_state.is_setting_string_delimiter = true;
-                symbol_state.could_be_double.clear();
-                return None;
-            }
-            if DOUBLE_OPERATORS.map(|v| v[len]).contains(&c_str)
-            || DOUBLE_KEYWORDS .map(|v| v[len]).contains(&c_str) {
-                char_buf.push(character);
-                let identifier = char_buf.to_string();
+            // Both characters have to form the double symbol, `<=` is one
+            // but `<+` is not
+            let mut identifier = String::from_iter(
+                symbol_state.could_be_double.clone()
+            );
+            identifier.push(character);
+            if DOUBLE_OPERATORS.iter()
+                .chain(DOUBLE_KEYWORDS.iter())
+                .any(|double| double.concat() == identifier) {
                 let s = Symbol::new(
                     identifier,
                     cursor.clone()
@@ -200,9 +280,14 @@ impl Parser {
             if SINGLE_OPERATORS.contains(single_maybe_str)
             || SINGLE_KEYWORDS .contains(single_maybe_str) {
                 let identifier = single_maybe;
+                // It ended at the character before this one
                 let s = Symbol::new(
                     identifier,
-                    cursor.clone()
+                    Cursor {
+                        pos: cursor.pos - 1,
+                        column: cursor.column - 1,
+                        line: cursor.line
+                    }
                 );
                 symbol_state.could_be_double.clear();
                 if let Some(mut next_ss) = self.read_symbol(
@@ -219,24 +304,40 @@ impl Parser {
                 return Some(vec![s]);
             }
         }
-        if character == symbol_state.current_string_delimiter.clone() {
-            if !symbol_state.is_in_string {
-                symbol_state.is_in_string = true;
-                return None;
-            } else {
-                symbol_state.is_in_string = false;
-                // reset string delimiter to default
-                symbol_state.current_string_delimiter = self.config.string_delimiter;
-                let identifier = char_buf.to_string();
-                char_buf.clear();
-                if identifier.len() > 0 {
-                    let result = Symbol::new(
-                        identifier,
-                        cursor.clone()
-                    );
-                    return Some(vec![result]);
-                }
-            }
+        // `qq*Hello "World"*`, the character right after `qq` delimits the
+        // string until it shows up again
+        if !symbol_state.is_in_string
+            && char_buf.strip_suffix(character) == Some(STR_DELIMETER_DECLARATOR[0])
+            && is_temporary_delimiter(character) {
+            symbol_state.is_in_string = true;
+            symbol_state.current_string_delimiter = character;
+            symbol_state.string_start = Cursor {
+                pos: cursor.pos - 2,
+                column: cursor.column - 2,
+                line: cursor.line,
+            };
+            return None;
+        }
+        // Char literals are read like strings, `` `a` ``
+        let is_delimiter = self.is_string_delimiter(character) || character == CHR_DELIMITER;
+        if !symbol_state.is_in_string && is_delimiter {
+            symbol_state.is_in_string = true;
+            symbol_state.current_string_delimiter = character;
+            symbol_state.string_start = *cursor;
+            return None;
+        }
+        if symbol_state.is_in_string && character == symbol_state.current_string_delimiter {
+            symbol_state.is_in_string = false;
+            let identifier = char_buf.to_string();
+            char_buf.clear();
+            // Strings can span lines, the start cannot be counted back
+            let result = Symbol {
+                symbol: identifier,
+                start: symbol_state.string_start,
+                end: cursor.clone(),
+                source: SourceId::default(),
+            };
+            return Some(vec![result]);
         }
         if symbol_state.is_in_string {
             return None;
@@ -245,8 +346,6 @@ impl Parser {
             .map(|v| v[0]).contains(&c_str)
         || DOUBLE_KEYWORDS 
             .map(|v| v[0]).contains(&c_str)
-        || STR_DELIMETER_DECLARATOR_DUO 
-            .map(|v| v[0]).contains(&c_str)
             {
             symbol_state.could_be_double.push(character);
             char_buf.pop();
@@ -268,6 +367,14 @@ impl Parser {
             }
             return None;
         }
+        // The decimal point of a float literal, `1.5` is a single symbol
+        if character == '.' {
+            let mut number = char_buf.to_string();
+            number.pop();
+            if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+        }
         if DELIMITERS.contains(&c_str)
         || OPEN_SYMBOLS.contains(&c_str) 
         || CLOSE_SYMBOLS.contains(&c_str) 
@@ -310,111 +417,181 @@ impl Parser {
         None
     }
 
-    pub fn register_functions(
-        self: &mut Self,
-        functions: Vec<(String, String)>
-    ) -> Result<(), ()> {
-        for (call, function) in functions {
-            if let Some(_) = self.functions.get(&call) {
-                return Err(());
+    /// Strings can always be delimited by double and single quotes, besides
+    /// the global delimiter
+    fn is_string_delimiter(self: &Self, character: char) -> bool {
+        return character == '"'
+            || character == '\''
+            || character == self.config.string_delimiter;
+    }
+
+    /// What is inside of a string symbol, `"Hello"`, `'Hello'` or one with
+    /// a temporary delimiter, `qq*Hello*`, with its escapes decoded
+    fn string_literal(
+        self: &Self,
+        symbol: &Symbol,
+    ) -> Option<Result<String, Vec<Diagnostic>>> {
+        let temporary = symbol.symbol
+            .strip_prefix(STR_DELIMETER_DECLARATOR[0])
+            .filter(|rest| rest.chars().next().is_some_and(is_temporary_delimiter));
+        let (delimited, delimiter) = match temporary {
+            Some(rest) => (rest, rest.chars().next()?),
+            None => {
+                let first = symbol.symbol.chars().next()?;
+                if !self.is_string_delimiter(first) {
+                    return None;
+                }
+                (symbol.symbol.as_str(), first)
             }
-            self.functions.insert(call, function);
+        };
+        let literal = delimited
+            .strip_prefix(delimiter)?
+            .strip_suffix(delimiter)?;
+        // Skip the delimiter, and `qq` before it
+        let mut start = symbol.start;
+        let prefix = symbol.symbol.len() - delimited.len() + delimiter.len_utf8();
+        for character in symbol.symbol[..prefix].chars() {
+            advance(&mut start, character);
         }
-        Ok(())
+        return Some(unescape(literal, start, delimiter));
+    }
+
+    /// The character of a char literal, `` `a` ``, `` `\n` ``
+    fn char_literal(self: &Self, symbol: &Symbol) -> Option<Result<char, Vec<Diagnostic>>> {
+        let literal = symbol.symbol
+            .strip_prefix(CHR_DELIMITER)?
+            .strip_suffix(CHR_DELIMITER)?;
+        let mut start = symbol.start;
+        advance(&mut start, CHR_DELIMITER);
+        let decoded = match unescape(literal, start, CHR_DELIMITER) {
+            Ok(decoded) => decoded,
+            Err(diagnostics) => return Some(Err(diagnostics)),
+        };
+        let mut chars = decoded.chars();
+        if let (Some(chr), None) = (chars.next(), chars.next()) {
+            return Some(Ok(chr));
+        }
+        return Some(Err(vec![Diagnostic::error(
+            format!("Char literals hold one character, found {}", decoded.chars().count()),
+            symbol,
+        ).with_note("strings are written between quotes, `\"text\"`".to_string())]));
     }
 
     pub fn tokenize_symbols(
         self: &mut Self, 
         symbols: Vec<Symbol>
-    ) -> Result<Vec<Node>, ParseError> {
+    ) -> Result<Vec<Node>, Vec<Diagnostic>> {
+        let mut diagnostics: Vec<Diagnostic> = vec![];
         let mut nodes: Vec<Node> = vec![];
         let mut working_id: u64 = 0;
         nodes.push(Node::root());
         let mut carryover: Conveyor<Symbol> = Conveyor::new(16);
-        let mut scope = Scope::default();
-        for symbol in symbols {
-            let next_id = nodes.len() as u64;
-            let mut working_node = nodes.get(
-                working_id as usize
-            ).unwrap().clone();
-            print!("{} ", symbol.symbol);
-            if let Some(lex_result) = self.lex(
-                &working_node,
-                &symbol,
-                &mut carryover,
-                next_id,
-                &scope,
-            ) {
+        // Trace of the lexer, only shown when debugging
+        let debug = self.config.debbuging;
+        let mut symbols = symbols.into_iter().peekable();
+        while let Some(symbol) = symbols.next() {
+            // A symbol can be lexed more than once when it closes more than
+            // the working node
+            let mut retry = true;
+            while retry {
+                retry = false;
+                let next_id = nodes.len() as u64;
+                let mut working_node = nodes.get(
+                    working_id as usize
+                ).unwrap().clone();
+                if debug { print!("{} ", symbol.symbol); }
+                let lex_result = self.lex(
+                    &working_node,
+                    &nodes,
+                    &symbol,
+                    symbols.peek(),
+                    &mut carryover,
+                    next_id,
+                );
+                let lex_result = match lex_result {
+                    Some(lex_result) => lex_result,
+                    None => {
+                        if !DELIMITERS.contains(&symbol.symbol.as_str())
+                        && !SEPARATORS.contains(&symbol.symbol.as_str()) {
+                            diagnostics.push(Diagnostic::error(
+                                format!("Unexpected `{}`", symbol.symbol),
+                                &symbol,
+                            ).with_note(format!(
+                                "while parsing {}", working_node.token
+                            )));
+                        }
+                        continue;
+                    }
+                };
                 for result in lex_result {
-                    print!("{} ", result.short_code());
+                    if debug { print!("{} ", result.short_code()); }
                     match result {
                         LexResult::New(node) => {
-                            print!("{} {} ", node.id, node.token);
-                            // If the new node is a variable add them to scope
-                            match node.token.clone() {
-                                TokenTypes::Variable(var) => {
-                                    scope.insert_variable(var)
-                                        .unwrap();
-                                }
-                                TokenTypes::Function(fun) => {
-                                    scope.insert_function(fun)
-                                        .unwrap();
-                                    // Clear variable scope
-                                    scope.variables.clear();
-                                }
-                                _ => {}
+                            if debug { print!("{} {} ", node.id, node.token); }
+                            // Link the new node to its parent
+                            if let Some(parent) = nodes.get_mut(node.parent as usize) {
+                                parent.children.push(node.id);
                             }
                             nodes.push(node);
                         }
                         LexResult::ChangeTo(node_id) => {
-                            print!("{}->{} ", working_node.id, node_id);
+                            if debug { print!("{}->{} ", working_node.id, node_id); }
                             working_id = node_id;
                         }
                         LexResult::Up => {
-                            print!("{}->{} ", working_node.id, working_node.parent);
-                            // If the exit is from a Function node, the scope
-                            // should be reset for variables
-                            match working_node.token.clone() {
-                                TokenTypes::Function(_) => {
-                                    println!("{:?}", scope.variables);
-                                }
-                                _ => {}
+                            if debug { print!("{}->{} ", working_node.id, working_node.parent); }
+                            // Expressions are complete once their node closes
+                            if let Err(diagnostic) = expression::build(&mut nodes, working_node.id, &self.symbols) {
+                                diagnostics.push(diagnostic);
                             }
                             working_id = working_node.parent;
                             working_node = nodes.get(working_id as usize).unwrap().clone();
                         }
-                        _ => {},
+                        LexResult::Skip => {
+                            symbols.next();
+                        }
+                        LexResult::Retry => {
+                            retry = true;
+                        }
+                        LexResult::Error(diagnostic) => {
+                            diagnostics.push(diagnostic);
+                        }
+                        LexResult::None => {},
                     }
                 }
+                if debug { println!(); }
             }
-            println!();
         }
-        println!("{:?}", working_id);
+        if debug { println!("{:?}", working_id); }
+        // Everything that was opened should be closed by the end of the file
+        if working_id != 0 {
+            let working_node = &nodes[working_id as usize];
+            diagnostics.push(Diagnostic::at_node(
+                format!("Unexpected end of file while parsing {}", working_node.token),
+                working_node,
+            ).with_note("this was never closed".to_string()));
+        }
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
         Ok(nodes)
     }
 
     pub fn lex(
         self: &mut Self,
         working_node: &Node,
+        nodes: &[Node],
         symbol: &Symbol,
+        next_symbol: Option<&Symbol>,
         carryover: &mut Conveyor<Symbol>,
         next_id: u64,
-        scope: &Scope,
     ) -> Option<Vec<LexResult>> {
         let c_symbol = symbol.symbol.as_str();
+        let parent_token = nodes.get(working_node.parent as usize)
+            .map(|parent| parent.token.clone())
+            .unwrap_or_default();
         match working_node.token {
             TokenTypes::Function(_) => {
-                if let Some(carry) = carryover.last() {
-                    match carry.symbol.as_str() {
-                        "}" => {
-                            carryover.pop();
-                            return Some(vec![
-                                LexResult::Up,
-                            ]);
-                        }
-                        _ => {},
-                    }
-                }
                 match c_symbol {
                     "(" => {
                         let result = Node::new(
@@ -442,6 +619,48 @@ impl Parser {
                             LexResult::ChangeTo(next_id)
                         ]);
                     }
+                    // Type parameters, `#first<t>`
+                    "<" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Generic,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id)
+                        ]);
+                    }
+                    // Imported from another file, `#multiply@dep.nl`
+                    "@" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Import,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id)
+                        ]);
+                    }
+                    // Functions of a struct, `#nameplate^person`
+                    "^" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Implement,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id)
+                        ]);
+                    }
                     // One liner functions
                     "<<" => {
                         let result = Node::new(
@@ -466,7 +685,7 @@ impl Parser {
                     }
                     _ => {
                         // Return type?
-                        if let Ok(operator) = symbol.symbol.clone().try_into() {
+                        if let Some(operator) = data_type(c_symbol) {
                             let result = Node::new(
                                 next_id,
                                 TokenTypes::Type(operator),
@@ -476,11 +695,79 @@ impl Parser {
                             );
                             return Some(vec![
                                 LexResult::New(result),
+                                LexResult::ChangeTo(next_id)
                             ]);
                         }
                     },
                 }
             }
+            // The path goes until the end of the line and closes what was
+            // imported with it
+            TokenTypes::Import => {
+                if DELIMITERS.contains(&c_symbol) {
+                    return Some(vec![
+                        LexResult::Up,
+                        LexResult::Up,
+                    ]);
+                }
+                let result = Node::new(
+                    next_id,
+                    TokenTypes::Path(c_symbol.to_string()),
+                    symbol.start,
+                    working_node.id,
+                    symbol.len()
+                );
+                return Some(vec![
+                    LexResult::New(result),
+                ]);
+            }
+            // Exports end with the declaration in them
+            TokenTypes::Export if !working_node.children.is_empty() => {
+                return Some(vec![
+                    LexResult::Up,
+                    LexResult::Retry,
+                ]);
+            }
+            TokenTypes::Root if c_symbol == "<<" => {
+                let result = Node::new(
+                    next_id,
+                    TokenTypes::Export,
+                    symbol.start,
+                    working_node.id,
+                    symbol.len()
+                );
+                return Some(vec![
+                    LexResult::New(result),
+                    LexResult::ChangeTo(next_id)
+                ]);
+            }
+            TokenTypes::Implement => {
+                match c_symbol {
+                    // The parameters are part of the function and the end of
+                    // an extension is part of the struct
+                    "(" | ";" | "\n" => {
+                        return Some(vec![
+                            LexResult::Up,
+                            LexResult::Retry,
+                        ]);
+                    }
+                    _ => {
+                        if let Some(operator) = data_type(c_symbol) {
+                            let result = Node::new(
+                                next_id,
+                                TokenTypes::Type(operator),
+                                symbol.start,
+                                working_node.id,
+                                symbol.len()
+                            );
+                            return Some(vec![
+                                LexResult::New(result),
+                                LexResult::ChangeTo(next_id),
+                            ]);
+                        }
+                    }
+                }
+            }
             TokenTypes::Params => {
                 match c_symbol {
                     ")" => {
@@ -492,7 +779,7 @@ impl Parser {
                         return None;
                     }
                     _ => {
-                        if let Ok(operator) = symbol.symbol.clone().try_into() {
+                        if let Some(operator) = data_type(c_symbol) {
                             let result = Node::new(
                                 next_id,
                                 TokenTypes::Type(operator),
@@ -513,7 +800,7 @@ impl Parser {
                     // Support generic<types>
                     "<" => {
                         let result = Node::new(
-                            next_id, 
+                            next_id,
                             TokenTypes::Generic,
                             symbol.start,
                             working_node.id,
@@ -524,6 +811,28 @@ impl Parser {
                             LexResult::ChangeTo(next_id)
                         ]);
                     },
+                    // The type of a cast ends the cast as well, `a~flt + b`
+                    _ if parent_token == TokenTypes::Cast => {
+                        return Some(vec![
+                            LexResult::Up,
+                            LexResult::Up,
+                            LexResult::Retry,
+                        ]);
+                    }
+                    // Return types end where the body starts, the struct of
+                    // a function where its parameters start and the types
+                    // in a generic where the next one starts
+                    _ if matches!(
+                        parent_token,
+                        TokenTypes::Function(_)
+                        | TokenTypes::Implement
+                        | TokenTypes::Generic
+                    ) => {
+                        return Some(vec![
+                            LexResult::Up,
+                            LexResult::Retry,
+                        ]);
+                    }
                     _ => {
                         let result = Node::new(
                             next_id,
@@ -546,11 +855,25 @@ impl Parser {
                             LexResult::Up,
                         ]);
                     }
+                    // `vec<vec<int>>` closes both generics at once
+                    ">>" => {
+                        let owner = nodes.get(working_node.parent as usize);
+                        let is_nested = owner
+                            .and_then(|owner| nodes.get(owner.parent as usize))
+                            .is_some_and(|outer| outer.token == TokenTypes::Generic);
+                        if is_nested {
+                            return Some(vec![
+                                LexResult::Up,
+                                LexResult::Up,
+                                LexResult::Up,
+                            ]);
+                        }
+                    }
                     "," => {
                         return None;
                     }
                     _ => {
-                        if let Ok(operator) = symbol.symbol.clone().try_into() {
+                        if let Some(operator) = data_type(c_symbol) {
                             let result = Node::new(
                                 next_id,
                                 TokenTypes::Type(operator),
@@ -560,6 +883,7 @@ impl Parser {
                             );
                             return Some(vec![
                                 LexResult::New(result),
+                                LexResult::ChangeTo(next_id),
                             ]);
                         }
                     }
@@ -568,12 +892,83 @@ impl Parser {
             TokenTypes::Body => {
                 match c_symbol {
                     "}" => {
-                        // For checking if a function needs to be closed
-                        carryover.push(symbol.clone());
+                        // Closing the body of a function or a conditional
+                        // closes them as well
+                        match parent_token {
+                            TokenTypes::Function(_)
+                            | TokenTypes::If
+                            | TokenTypes::Loop => {
+                                return Some(vec![
+                                    LexResult::Up,
+                                    LexResult::Up,
+                                ]);
+                            }
+                            _ => {}
+                        }
                         return Some(vec![
                             LexResult::Up
                         ]);
                     }
+                    "?" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::If,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        let condition = Node::new(
+                            next_id + 1,
+                            TokenTypes::Condition,
+                            symbol.start,
+                            next_id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::New(condition),
+                            LexResult::ChangeTo(next_id + 1),
+                        ]);
+                    }
+                    // `<< !` breaks out of the loop
+                    "<<" if next_symbol.map(|next| next.symbol.as_str()) == Some("!") => {
+                        let end = next_symbol.unwrap().end;
+                        if !is_inside(nodes, working_node, |token| *token == TokenTypes::Loop) {
+                            return Some(vec![
+                                LexResult::Error(Diagnostic::new(
+                                    "Cannot break outside of a loop".to_string(),
+                                    symbol.start,
+                                    end,
+                                ).with_note("loops are declared with `! { ... }`".to_string())),
+                                LexResult::Skip,
+                            ]);
+                        }
+                        let mut result = Node::new(
+                            next_id,
+                            TokenTypes::Break,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        result.len = (end.pos - symbol.start.pos + 1) as usize;
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::Skip,
+                        ]);
+                    }
+                    "!" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Loop,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id),
+                        ]);
+                    }
                     "<<" => {
                         let result = Node::new(
                             next_id,
@@ -596,8 +991,14 @@ impl Parser {
                         ]);
                     }
                     _ => {
-                        // Could be a type to start a statement
-                        if let Ok(operator) = symbol.symbol.clone().try_into() {
+                        // Could be a type to start a statement, structs are
+                        // told apart from variables by the name after them
+                        let declared = match next_symbol {
+                            Some(next) if is_identifier(&next.symbol)
+                                || next.symbol == "<" => data_type(c_symbol),
+                            _ => None,
+                        };
+                        if let Some(operator) = declared {
                             let statement = Node::new(
                                 next_id,
                                 TokenTypes::Statement,
@@ -614,15 +1015,15 @@ impl Parser {
                             );
                             return Some(vec![
                                 LexResult::New(statement),
-                                LexResult::ChangeTo(next_id),
                                 LexResult::New(result),
+                                LexResult::ChangeTo(next_id + 1),
                             ]);
                         }
-                        // Could be a function call
-                        if let Some(function) = self.functions.get(&symbol.symbol) {
+                        // Could be a call to a native function
+                        if self.natives.contains(&symbol.symbol) {
                             let result = Node::new(
                                 next_id,
-                                TokenTypes::Call(function.clone()),
+                                TokenTypes::Call(symbol.symbol.clone()),
                                 symbol.start,
                                 working_node.id,
                                 symbol.len()
@@ -632,21 +1033,196 @@ impl Parser {
                                 LexResult::ChangeTo(next_id),
                             ])
                         }
-                    },
-                }
-            }
-            TokenTypes::Call(_) => {
-                match c_symbol {
-                    "(" => {
-                        let result = Node::new(
-                            next_id, 
-                            TokenTypes::CallParams,
-                            symbol.start,
-                            working_node.id,
-                            symbol.len()
-                        );
-                        return Some(vec![
-                            LexResult::New(result),
+                        if !is_identifier(c_symbol) {
+                            return None;
+                        }
+                        // Could be a call to a function, it may be declared
+                        // further down so it is left to the checker
+                        if is_call(next_symbol) {
+                            let result = Node::new(
+                                next_id,
+                                TokenTypes::Call(symbol.symbol.clone()),
+                                symbol.start,
+                                working_node.id,
+                                symbol.len()
+                            );
+                            return Some(vec![
+                                LexResult::New(result),
+                                LexResult::ChangeTo(next_id),
+                            ])
+                        }
+                        // Could be a reassignment of a variable
+                        let statement = Node::new(
+                            next_id,
+                            TokenTypes::Statement,
+                            symbol.start,
+                            working_node.id,
+                            0,
+                        );
+                        let result = Node::new(
+                            next_id + 1,
+                            TokenTypes::Variable(symbol.symbol.clone()),
+                            symbol.start,
+                            next_id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(statement),
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id),
+                        ]);
+                    },
+                }
+            }
+            TokenTypes::Struct(_) => {
+                match c_symbol {
+                    // Type parameters, `^conveyor<t>`
+                    "<" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Generic,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id)
+                        ]);
+                    }
+                    "{" => {
+                        return Some(vec![
+                            LexResult::None,
+                        ]);
+                    }
+                    "}" => {
+                        return Some(vec![
+                            LexResult::Up,
+                        ]);
+                    }
+                    // Imported from another file, `^map@std`
+                    "@" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Import,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id)
+                        ]);
+                    }
+                    // Extensions, `^vec<t>^conveyor<t>;`, the struct after
+                    // the second `^` gets the functions of the first one
+                    "^" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Implement,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id)
+                        ]);
+                    }
+                    _ if DELIMITERS.contains(&c_symbol) => {
+                        let is_extension = working_node.children
+                            .iter()
+                            .any(|id| nodes[*id as usize].token == TokenTypes::Implement);
+                        if is_extension {
+                            return Some(vec![
+                                LexResult::Up,
+                            ]);
+                        }
+                    }
+                    _ => {
+                        // Fields, `int id;`
+                        if let Some(operator) = data_type(c_symbol) {
+                            let result = Node::new(
+                                next_id,
+                                TokenTypes::Type(operator),
+                                symbol.start,
+                                working_node.id,
+                                symbol.len()
+                            );
+                            return Some(vec![
+                                LexResult::New(result),
+                                LexResult::ChangeTo(next_id)
+                            ]);
+                        }
+                    }
+                }
+            }
+            TokenTypes::StructLiteral => {
+                match c_symbol {
+                    "}" => {
+                        return Some(vec![
+                            LexResult::Up,
+                        ]);
+                    }
+                    // `id: 69`
+                    _ if is_identifier(c_symbol)
+                        && next_symbol.map(|next| next.symbol.as_str()) == Some(":") => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Field(symbol.symbol.clone()),
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id),
+                            LexResult::Skip,
+                        ]);
+                    }
+                    _ => {}
+                }
+            }
+            TokenTypes::Field(_) => {
+                match c_symbol {
+                    "," => {
+                        return Some(vec![
+                            LexResult::Up,
+                        ]);
+                    }
+                    // The last field does not need a comma
+                    "}" => {
+                        return Some(vec![
+                            LexResult::Up,
+                            LexResult::Retry,
+                        ]);
+                    }
+                    _ => {
+                        if let Some(lex_results) = self.lex_literals(
+                            symbol,
+                            c_symbol,
+                            working_node,
+                            nodes,
+                            next_id,
+                            next_symbol,
+                        ) {
+                            return Some(lex_results);
+                        }
+                    }
+                }
+            }
+            TokenTypes::Call(_) => {
+                match c_symbol {
+                    "(" => {
+                        let result = Node::new(
+                            next_id, 
+                            TokenTypes::CallParams,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
                             LexResult::ChangeTo(next_id)
                         ]);
                     },
@@ -661,13 +1237,26 @@ impl Parser {
                             LexResult::Up,
                         ]);
                     }
+                    "," => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Separator,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                        ]);
+                    }
                     _ => {
                         if let Some(lex_results) = self.lex_literals(
                             symbol,
                             c_symbol,
                             working_node,
+                            nodes,
                             next_id,
-                            scope
+                            next_symbol,
                         ) {
                             return Some(lex_results);
                         }
@@ -675,17 +1264,164 @@ impl Parser {
                 }
             }
             TokenTypes::Statement => {
-                if DELIMITERS.contains(&c_symbol) {
+                // The end of a scope also ends the statement, the `}` still
+                // has to close the scope
+                let closes_scope = c_symbol == "}";
+                if DELIMITERS.contains(&c_symbol) || closes_scope {
+                    // Statements opened by a return also close the return
+                    let mut results = match parent_token {
+                        TokenTypes::Return => vec![
+                            LexResult::Up,
+                            LexResult::Up,
+                        ],
+                        // Short returns are the whole function
+                        TokenTypes::ShortReturn => vec![
+                            LexResult::Up,
+                            LexResult::Up,
+                            LexResult::Up,
+                        ],
+                        _ => vec![
+                            LexResult::Up,
+                        ],
+                    };
+                    if closes_scope {
+                        results.push(LexResult::Retry);
+                    }
+                    return Some(results);
+                }
+                if let Some(lex_results) = self.lex_literals(
+                    symbol,
+                    c_symbol,
+                    working_node,
+                    nodes,
+                    next_id,
+                    next_symbol,
+                ) {
+                    return Some(lex_results);
+                }
+            }
+            // Items can be on lines of their own, the end of a line after an
+            // item separates it from the next one like a comma
+            TokenTypes::VecLiteral => {
+                let ends_item = working_node.children
+                    .last()
+                    .and_then(|id| nodes.get(*id as usize))
+                    .is_some_and(|last| !matches!(
+                        last.token,
+                        TokenTypes::Separator | TokenTypes::Operator(_)
+                    ));
+                match c_symbol {
+                    "]" => {
+                        return Some(vec![
+                            LexResult::Up,
+                        ]);
+                    }
+                    "\n" if !ends_item => {
+                        return Some(vec![
+                            LexResult::None,
+                        ]);
+                    }
+                    "," | "\n" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Separator,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                        ]);
+                    }
+                    _ => {
+                        if let Some(lex_results) = self.lex_literals(
+                            symbol,
+                            c_symbol,
+                            working_node,
+                            nodes,
+                            next_id,
+                            next_symbol,
+                        ) {
+                            return Some(lex_results);
+                        }
+                    }
+                }
+            }
+            TokenTypes::Index => {
+                if c_symbol == "]" {
+                    return Some(vec![
+                        LexResult::Up,
+                    ]);
+                }
+                if let Some(lex_results) = self.lex_literals(
+                    symbol,
+                    c_symbol,
+                    working_node,
+                    nodes,
+                    next_id,
+                    next_symbol,
+                ) {
+                    return Some(lex_results);
+                }
+            }
+            TokenTypes::Group => {
+                if c_symbol == ")" {
+                    return Some(vec![
+                        LexResult::Up,
+                    ]);
+                }
+                if let Some(lex_results) = self.lex_literals(
+                    symbol,
+                    c_symbol,
+                    working_node,
+                    nodes,
+                    next_id,
+                    next_symbol,
+                ) {
+                    return Some(lex_results);
+                }
+            }
+            TokenTypes::Loop => {
+                match c_symbol {
+                    "{" => {
+                        let result = Node::new(
+                            next_id,
+                            TokenTypes::Body,
+                            symbol.start,
+                            working_node.id,
+                            symbol.len()
+                        );
+                        return Some(vec![
+                            LexResult::New(result),
+                            LexResult::ChangeTo(next_id),
+                        ]);
+                    }
+                    _ => {}
+                }
+            }
+            TokenTypes::Condition => {
+                // The condition ends where the body of the conditional starts
+                if c_symbol == "{" {
+                    let result = Node::new(
+                        next_id,
+                        TokenTypes::Body,
+                        symbol.start,
+                        working_node.parent,
+                        symbol.len()
+                    );
                     return Some(vec![
                         LexResult::Up,
+                        LexResult::New(result),
+                        LexResult::ChangeTo(next_id),
                     ]);
                 }
                 if let Some(lex_results) = self.lex_literals(
                     symbol,
                     c_symbol,
                     working_node,
+                    nodes,
                     next_id,
-                    scope
+                    next_symbol,
                 ) {
                     return Some(lex_results);
                 }
@@ -700,8 +1436,9 @@ impl Parser {
                     symbol,
                     c_symbol,
                     working_node,
+                    nodes,
                     next_id,
-                    scope
+                    next_symbol,
                 ) {
                     return Some(lex_results);
                 }
@@ -717,17 +1454,22 @@ impl Parser {
                     symbol,
                     c_symbol,
                     working_node,
+                    nodes,
                     next_id,
-                    scope
+                    next_symbol,
                 ) {
                     return Some(lex_results);
                 }
             }
             _ => {},
         }
-        if symbol.symbol == "#" {
+        let is_top_level = matches!(working_node.token, TokenTypes::Root | TokenTypes::Export);
+        if symbol.symbol == "#"
+        || (symbol.symbol == "^" && is_top_level) {
             carryover.push(symbol.clone());
-            return None;
+            return Some(vec![
+                LexResult::None,
+            ]);
         }
         if let Some(carry) = carryover.pop() {
             match carry.symbol.as_str() {
@@ -744,6 +1486,19 @@ impl Parser {
                         LexResult::ChangeTo(next_id)
                     ]);
                 },
+                "^" => {
+                    let result = Node::new(
+                        next_id,
+                        TokenTypes::Struct(symbol.symbol.clone()),
+                        symbol.start,
+                        working_node.id,
+                        symbol.len()
+                    );
+                    return Some(vec![
+                        LexResult::New(result),
+                        LexResult::ChangeTo(next_id)
+                    ]);
+                },
                 _ => {}
             }
         }
@@ -755,9 +1510,141 @@ impl Parser {
         symbol: &Symbol,
         c_symbol: &str,
         working_node: &Node,
+        nodes: &[Node],
         next_id: u64,
-        scope: &Scope,
+        next_symbol: Option<&Symbol>,
     ) -> Option<Vec<LexResult>>{
+        // Parenthesis right after `.name` or `:name` are its arguments
+        let previous = working_node.children
+            .last()
+            .and_then(|id| nodes.get(*id as usize));
+        if let Some(previous) = previous.filter(|_| c_symbol == "(") {
+            if matches!(previous.token, TokenTypes::Access(_) | TokenTypes::Static(_)) {
+                let result = Node::new(
+                    next_id,
+                    TokenTypes::CallParams,
+                    symbol.start,
+                    previous.id,
+                    symbol.len()
+                );
+                return Some(vec![
+                    LexResult::New(result),
+                    LexResult::ChangeTo(next_id),
+                ]);
+            }
+        }
+        // Indexing, `list[0]`
+        let is_operand = previous.is_some_and(|previous| !matches!(
+            previous.token,
+            TokenTypes::Operator(_) | TokenTypes::Separator
+        ));
+        if c_symbol == "[" && is_operand {
+            let result = Node::new(
+                next_id,
+                TokenTypes::Index,
+                symbol.start,
+                working_node.id,
+                symbol.len()
+            );
+            return Some(vec![
+                LexResult::New(result),
+                LexResult::ChangeTo(next_id),
+            ]);
+        }
+        if c_symbol == "[" {
+            let result = Node::new(
+                next_id,
+                TokenTypes::VecLiteral,
+                symbol.start,
+                working_node.id,
+                symbol.len()
+            );
+            return Some(vec![
+                LexResult::New(result),
+                LexResult::ChangeTo(next_id),
+            ]);
+        }
+        // Casts, `a~flt`, the type is lexed inside of the cast
+        if c_symbol == "~" {
+            let data_type = next_symbol.and_then(|next| data_type(&next.symbol));
+            let (next, data_type) = match (next_symbol, data_type) {
+                (Some(next), Some(data_type)) => (next, data_type),
+                _ => return Some(vec![
+                    LexResult::Error(Diagnostic::error(
+                        "Expected a type after `~`".to_string(),
+                        symbol,
+                    ).with_note("values are cast with `value~type`, `a~flt`".to_string())),
+                ]),
+            };
+            let cast = Node::new(
+                next_id,
+                TokenTypes::Cast,
+                symbol.start,
+                working_node.id,
+                symbol.len()
+            );
+            let result = Node::new(
+                next_id + 1,
+                TokenTypes::Type(data_type),
+                next.start,
+                next_id,
+                next.len()
+            );
+            return Some(vec![
+                LexResult::New(cast),
+                LexResult::New(result),
+                LexResult::ChangeTo(next_id + 1),
+                LexResult::Skip,
+            ]);
+        }
+        // Parenthesis that are not part of a call group an expression
+        if c_symbol == "(" {
+            let result = Node::new(
+                next_id,
+                TokenTypes::Group,
+                symbol.start,
+                working_node.id,
+                symbol.len()
+            );
+            return Some(vec![
+                LexResult::New(result),
+                LexResult::ChangeTo(next_id),
+            ]);
+        }
+        if c_symbol == "{" {
+            let result = Node::new(
+                next_id,
+                TokenTypes::StructLiteral,
+                symbol.start,
+                working_node.id,
+                symbol.len()
+            );
+            return Some(vec![
+                LexResult::New(result),
+                LexResult::ChangeTo(next_id),
+            ]);
+        }
+        // Field access and method calls, `employee.id`, `employee.name()`
+        // and functions of structs, `person:new()`
+        if c_symbol == "." || c_symbol == ":" {
+            if let Some(field) = next_symbol.filter(|next| is_identifier(&next.symbol)) {
+                let token = match c_symbol {
+                    "." => TokenTypes::Access(field.symbol.clone()),
+                    _ => TokenTypes::Static(field.symbol.clone()),
+                };
+                let result = Node::new(
+                    next_id,
+                    token,
+                    field.start,
+                    working_node.id,
+                    field.len()
+                );
+                return Some(vec![
+                    LexResult::New(result),
+                    LexResult::Skip,
+                ]);
+            }
+        }
         if OPERATORS.contains(&c_symbol) {
             let result = Node::new(
                 next_id,
@@ -773,12 +1660,11 @@ impl Parser {
                 LexResult::New(result)
             ])
         }
-        // Could be a string with a modified delimiter
-        if c_symbol.starts_with("q") {
-            let mut literal_str = c_symbol.to_string();
-            literal_str.remove(0);
-            literal_str.remove(0);
-            literal_str.remove(literal_str.len() - 1);
+        if let Some(literal) = self.string_literal(symbol) {
+            let (literal_str, diagnostics) = match literal {
+                Ok(literal_str) => (literal_str, vec![]),
+                Err(diagnostics) => (String::new(), diagnostics),
+            };
             let result = Node::new(
                 next_id,
                 TokenTypes::LiteralString(literal_str),
@@ -786,18 +1672,36 @@ impl Parser {
                 working_node.id,
                 symbol.len()
             );
-            return Some(vec![
-                LexResult::New(result),
-            ]);
+            let mut results: Vec<LexResult> = diagnostics
+                .into_iter()
+                .map(LexResult::Error)
+                .collect();
+            results.push(LexResult::New(result));
+            return Some(results);
         }
-        // Could be a string
-        if c_symbol.starts_with("\"") {
-            let mut literal_str = c_symbol.to_string();
-            literal_str.remove(0);
-            literal_str.remove(literal_str.len() - 1);
+        if let Some(literal) = self.char_literal(symbol) {
+            let (literal_chr, diagnostics) = match literal {
+                Ok(literal_chr) => (literal_chr, vec![]),
+                Err(diagnostics) => (' ', diagnostics),
+            };
             let result = Node::new(
                 next_id,
-                TokenTypes::LiteralString(literal_str),
+                TokenTypes::LiteralChar(literal_chr),
+                symbol.start,
+                working_node.id,
+                symbol.len()
+            );
+            let mut results: Vec<LexResult> = diagnostics
+                .into_iter()
+                .map(LexResult::Error)
+                .collect();
+            results.push(LexResult::New(result));
+            return Some(results);
+        }
+        if c_symbol == "true" || c_symbol == "false" {
+            let result = Node::new(
+                next_id,
+                TokenTypes::LiteralBool(c_symbol == "true"),
                 symbol.start,
                 working_node.id,
                 symbol.len()
@@ -849,7 +1753,7 @@ impl Parser {
                 LexResult::New(result),
             ]);
         }
-        if scope.function_exists(&symbol.symbol) {
+        if self.natives.contains(&symbol.symbol) {
             let result = Node::new(
                 next_id,
                 TokenTypes::Call(symbol.symbol.clone()),
@@ -862,7 +1766,21 @@ impl Parser {
                 LexResult::ChangeTo(next_id),
             ]);
         }
-        if scope.variable_exists(&symbol.symbol) {
+        // Whether the function or variable exists is up to the checker
+        if is_identifier(c_symbol) && is_call(next_symbol) {
+            let result = Node::new(
+                next_id,
+                TokenTypes::Call(symbol.symbol.clone()),
+                symbol.start,
+                working_node.id,
+                symbol.len()
+            );
+            return Some(vec![
+                LexResult::New(result),
+                LexResult::ChangeTo(next_id),
+            ]);
+        }
+        if is_identifier(c_symbol) {
             let result = Node::new(
                 next_id,
                 TokenTypes::Variable(symbol.symbol.clone()),
@@ -877,3 +1795,160 @@ impl Parser {
         return None;
     }
 }
+
+/// Checks the node and its parents up until the function they are in
+pub fn is_inside(
+    nodes: &[Node],
+    node: &Node,
+    predicate: impl Fn(&TokenTypes) -> bool,
+) -> bool {
+    let mut current = node;
+    loop {
+        if predicate(&current.token) {
+            return true;
+        }
+        match current.token {
+            TokenTypes::Function(_) | TokenTypes::Root => return false,
+            _ => {}
+        }
+        current = match nodes.get(current.parent as usize) {
+            Some(parent) => parent,
+            None => return false,
+        };
+    }
+}
+
+/// Basic types by their keyword, any other identifier names a struct
+fn data_type(symbol: &str) -> Option<DataTypes> {
+    if let Ok(data_type) = symbol.to_string().try_into() {
+        return Some(data_type);
+    }
+    if is_identifier(symbol) {
+        return Some(DataTypes::User(symbol.to_string()));
+    }
+    None
+}
+
+/// Escapes every string has, the delimiter of the string can be escaped too
+const ESCAPES_NOTE: &str = "escapes are `\\n`, `\\t`, `\\\\`, `\\\"`, `\\'` and `\\u{...}`";
+
+/// Decodes the escapes in the contents of a string, `start` is where the
+/// contents start in the file
+fn unescape(literal: &str, start: Cursor, delimiter: char) -> Result<String, Vec<Diagnostic>> {
+    let mut decoded = String::new();
+    let mut diagnostics = vec![];
+    let mut cursor = start;
+    let mut chars = literal.chars().peekable();
+    while let Some(character) = chars.next() {
+        if character != '\\' {
+            decoded.push(character);
+            advance(&mut cursor, character);
+            continue;
+        }
+        let escape_start = cursor;
+        advance(&mut cursor, character);
+        let escaped = match chars.next() {
+            Some(escaped) => escaped,
+            None => break,
+        };
+        let escaped_start = cursor;
+        advance(&mut cursor, escaped);
+        match escaped {
+            'n' => decoded.push('\n'),
+            't' => decoded.push('\t'),
+            '\\' | '"' | '\'' => decoded.push(escaped),
+            _ if escaped == delimiter => decoded.push(escaped),
+            // `\u{1F30C}`, up to 6 hexadecimal digits
+            'u' => {
+                let mut digits = String::new();
+                let mut is_closed = false;
+                if chars.peek() == Some(&'{') {
+                    advance(&mut cursor, '{');
+                    chars.next();
+                    while let Some(digit) = chars.next_if(|digit| !digit.is_whitespace()) {
+                        advance(&mut cursor, digit);
+                        if digit == '}' {
+                            is_closed = true;
+                            break;
+                        }
+                        digits.push(digit);
+                    }
+                }
+                let end = Cursor {
+                    pos: cursor.pos - 1,
+                    line: cursor.line,
+                    column: cursor.column - 1,
+                };
+                if !is_closed {
+                    diagnostics.push(Diagnostic::new(
+                        "Expected `{` and `}` around the code point of the escape".to_string(),
+                        escape_start,
+                        end,
+                    ).with_note("unicode escapes are written as `\\u{1F30C}`".to_string()));
+                    continue;
+                }
+                let code = match digits.len() {
+                    1..=6 => u32::from_str_radix(&digits, 16).ok(),
+                    _ => None,
+                };
+                match code.and_then(char::from_u32) {
+                    Some(decoded_char) => decoded.push(decoded_char),
+                    None => diagnostics.push(Diagnostic::new(
+                        format!("`{}` is not a unicode code point", digits),
+                        escape_start,
+                        end,
+                    ).with_note("code points have up to 6 hexadecimal digits and go up to `10FFFF`".to_string())),
+                }
+            }
+            _ => diagnostics.push(Diagnostic::new(
+                format!("Unknown escape `\\{}`", escaped),
+                escape_start,
+                escaped_start,
+            ).with_note(ESCAPES_NOTE.to_string())),
+        }
+    }
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+    Ok(decoded)
+}
+
+/// Moves the cursor past the character
+fn advance(cursor: &mut Cursor, character: char) {
+    cursor.pos += 1;
+    if character == '\n' {
+        cursor.line += 1;
+        cursor.column = 1;
+    } else {
+        cursor.column += 1;
+    }
+}
+
+/// Characters that can follow `qq` to delimit a string. Names, spaces and
+/// what can end an expression are left out so `qq` can still be a variable,
+/// `print(qq)`
+fn is_temporary_delimiter(character: char) -> bool {
+    let c_string = character.to_string();
+    let c_str = c_string.as_str();
+    return !character.is_alphanumeric()
+        && character != '_'
+        && !character.is_whitespace()
+        && !DELIMITERS.contains(&c_str)
+        && !SEPARATORS.contains(&c_str)
+        && !CLOSE_SYMBOLS.contains(&c_str);
+}
+
+/// Identifiers followed by parenthesis are calls
+fn is_call(next_symbol: Option<&Symbol>) -> bool {
+    next_symbol.map(|next| next.symbol.as_str()) == Some("(")
+}
+
+/// Identifiers start with a letter or an underscore
+pub fn is_identifier(symbol: &str) -> bool {
+    let mut chars = symbol.chars();
+    match chars.next() {
+        Some(first) => (first.is_alphabetic() || first == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_'),
+        None => false,
+    }
+}
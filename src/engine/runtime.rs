@@ -0,0 +1,219 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use hashbrown::HashMap;
+
+use super::checker::{Exports, Params};
+use super::compiler::{compiled_name, CompileError, Compiler};
+use super::config::Config;
+use super::core::{self, fits, Fields, Native, Natives, Structs};
+use super::loader::{LoadError, Loader, Module};
+use super::types::TypeSignature;
+use super::vm::{RuntimeError, Value, Vm};
+
+#[derive(Debug)]
+pub enum EngineError {
+    /// The program has errors, they are reported in the file they are in
+    Load(LoadError),
+    Compile(CompileError),
+    Runtime(RuntimeError),
+    /// A function or struct of the host with a name that is already taken
+    Taken(String),
+    /// No function of that name was loaded
+    UnknownFunction(String),
+    /// A value the host called a function with that does not have the type
+    /// of its parameter, counted from 1
+    Argument {
+        function: String,
+        position: usize,
+        expected: TypeSignature,
+    },
+}
+
+impl Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Load(error) if error.diagnostics.is_empty() => {
+                write!(f, "Source file {} not found", error.path)
+            }
+            Self::Load(error) => {
+                let lines: Vec<String> = error.diagnostics
+                    .iter()
+                    .map(|diagnostic| format!(
+                        "{}:{}:{}: {}",
+                        diagnostic.source, diagnostic.start.line, diagnostic.start.column, diagnostic.message
+                    ))
+                    .collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            Self::Compile(error) => write!(f, "{}", error),
+            Self::Runtime(error) => write!(f, "{}", error),
+            Self::Taken(name) => write!(f, "`{}` is already registered", name),
+            Self::UnknownFunction(name) => write!(f, "Cannot find function `{}`", name),
+            Self::Argument { function, position, expected } => write!(
+                f, "Argument {} of `{}` has to be of type `{}`", position, function, expected
+            ),
+        }
+    }
+}
+
+/// Nebulang inside a Rust program. Functions and structs of the host are
+/// registered before loading the program that uses them, what the program
+/// prints is kept in the engine instead of going to stdout.
+pub struct Engine {
+    config: Config,
+    natives: Natives,
+    host: Exports,
+    output: Rc<RefCell<String>>,
+    /// The loaded program
+    vm: Option<Vm>,
+    /// Functions of the loaded program by the name they are compiled with,
+    /// the ones of the files it imports included
+    functions: HashMap<String, Callable>,
+}
+
+/// Parameters of a function of the loaded program, with the structs of the
+/// file it is declared in
+struct Callable {
+    params: Params,
+    structs: Rc<Structs>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        let output = Rc::new(RefCell::new(String::new()));
+        let printed = output.clone();
+        let natives = core::register_with(move |line| {
+            let mut printed = printed.borrow_mut();
+            printed.push_str(line);
+            printed.push('\n');
+        });
+        Self {
+            config: Config::new(""),
+            natives,
+            host: Exports::default(),
+            output,
+            vm: None,
+            functions: HashMap::new(),
+        }
+    }
+
+    /// Global string delimiter of the programs loaded after it, `--qq`
+    pub fn with_string_delimiter(mut self: Self, delimiter: char) -> Self {
+        self.config.string_delimiter = delimiter;
+        return self;
+    }
+
+    /// Makes a function of the host callable by name, its parameters and
+    /// return type are checked like the ones of any other function
+    pub fn register_function(self: &mut Self, name: &str, native: Native) -> Result<(), EngineError> {
+        self.natives
+            .register(name, native)
+            .map_err(|_| EngineError::Taken(name.to_string()))
+    }
+
+    /// Makes a struct of the host usable without importing it. Its values
+    /// are `Value::Struct` with the fields in the order they are given and
+    /// no kind.
+    pub fn register_struct(
+        self: &mut Self,
+        name: &str,
+        fields: Vec<(&str, TypeSignature)>,
+    ) -> Result<(), EngineError> {
+        let fields: Vec<(String, TypeSignature)> = fields
+            .into_iter()
+            .map(|(field, signature)| (field.to_string(), signature))
+            .collect();
+        let types = fields.iter().map(|(_, signature)| signature.clone()).collect();
+        if !self.host.host_struct(name, fields) {
+            return Err(EngineError::Taken(name.to_string()));
+        }
+        self.natives.register_struct(name, Fields { generics: vec![], types });
+        Ok(())
+    }
+
+    /// Loads a program held in memory, `name` names it in its errors. It
+    /// only reads files when it imports some, they are looked for next to
+    /// `name`.
+    pub fn load_source(self: &mut Self, name: &str, source: &str) -> Result<(), EngineError> {
+        let modules = Loader::new(&self.config_for(name))
+            .with_natives(self.natives.clone())
+            .with_host(self.host.clone())
+            .load_source(source)
+            .map_err(EngineError::Load)?;
+        self.compile(modules)
+    }
+
+    /// Loads the file and the files it imports
+    pub fn load_file(self: &mut Self, path: &str) -> Result<(), EngineError> {
+        let modules = Loader::new(&self.config_for(path))
+            .with_natives(self.natives.clone())
+            .with_host(self.host.clone())
+            .load_file()
+            .map_err(EngineError::Load)?;
+        self.compile(modules)
+    }
+
+    fn config_for(self: &Self, src: &str) -> Config {
+        let mut config = Config::new(src);
+        config.string_delimiter = self.config.string_delimiter;
+        return config;
+    }
+
+    fn compile(self: &mut Self, modules: Vec<Module>) -> Result<(), EngineError> {
+        let program = Compiler::emit_modules(&modules).map_err(EngineError::Compile)?;
+        self.functions.clear();
+        for module in &modules {
+            let analysis = &module.parser.analysis;
+            let structs = Rc::new(analysis.structs.clone());
+            for (name, params) in &analysis.params {
+                self.functions.insert(compiled_name(name, module.name.as_deref()), Callable {
+                    params: params.clone(),
+                    structs: structs.clone(),
+                });
+            }
+        }
+        self.vm = Some(Vm::with_natives(program, self.natives.clone()));
+        Ok(())
+    }
+
+    /// Calls a function of the loaded program, `nameplate^person` for the
+    /// functions of a struct and `twice@lib/math.nl` for the ones of the
+    /// files it imports. The values have to have the types of its parameters.
+    pub fn call(self: &mut Self, name: &str, args: Vec<Value>) -> Result<Value, EngineError> {
+        let unknown = || EngineError::UnknownFunction(name.to_string());
+        let vm = self.vm.as_mut().ok_or_else(unknown)?;
+        let index = vm.function_index(name).ok_or_else(unknown)?;
+        let Callable { params, structs } = self.functions.get(name).ok_or_else(unknown)?;
+        let mismatched = args
+            .iter()
+            .zip(params.types.iter())
+            .position(|(arg, expected)| !fits(arg, expected, &params.generics, structs));
+        if let Some(index) = mismatched {
+            return Err(EngineError::Argument {
+                function: name.to_string(),
+                position: index + 1,
+                expected: params.types[index].clone(),
+            });
+        }
+        vm.call(index, args).map_err(EngineError::Runtime)
+    }
+
+    /// Runs the #main function of the loaded program, returning its exit code
+    pub fn run(self: &mut Self, args: Vec<String>) -> Result<i32, EngineError> {
+        let vm = self.vm.as_mut().ok_or_else(|| EngineError::UnknownFunction("main".to_string()))?;
+        vm.run(args).map_err(EngineError::Runtime)
+    }
+
+    /// What the program printed since the last time it was taken
+    pub fn take_output(self: &mut Self) -> String {
+        std::mem::take(&mut *self.output.borrow_mut())
+    }
+}
@@ -19,27 +19,26 @@ pub const DOUBLE_KEYWORDS: [&'static [&'static str; 2]; 2] = [
     &["<","<"],
     &["/", "/"],
 ];
-pub const OPERATORS: [&'static str; 19] = [
+pub const OPERATORS: [&'static str; 20] = [
     "=", "*", "+", "-", "/", "%",
     "<", ">", "|", "&",
     "++", "--", "-=", "+=",
-    "==", ">=", "<=",
+    "==", "!=", ">=", "<=",
     "<<", ">>",
 ];
 pub const STR_DELIMETER_DECLARATOR: [&'static str; 1] = ["qq"];
-pub const STR_DELIMETER_DECLARATOR_DUO: [&'static [&'static str; 2]; 1] = [
-    &["q", "q"],
-];
+pub const CHR_DELIMITER: char = '`';
 pub const SINGLE_OPERATORS: [&'static str; 10] = [
     "=", "*", "+", "-", "/", "%",
     "<", ">", "|", "&",
 ];
-pub const DOUBLE_OPERATORS: [&'static [&'static str; 2]; 9] = [
+pub const DOUBLE_OPERATORS: [&'static [&'static str; 2]; 10] = [
     &["+","+"], // Increase Assignmet
     &["-","-"], // Decrease Assignment
     &["-","="], // Subtractional Assignment
     &["+","="], // Addition Assignment
     &["=","="],
+    &["!","="],
     &[">","="],
     &["<","="],
     &["<","<"],
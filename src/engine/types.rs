@@ -1,32 +1,38 @@
-use std::{fmt::Display};
+use std::{fmt::Display, rc::Rc};
+
+/// Name of the file or buffer the source code was read from
+pub type SourceId = Rc<str>;
 
 #[derive(Debug, Default, Clone)]
 pub struct Symbol {
     pub symbol: String,
     pub start: Cursor,
     pub end: Cursor,
+    pub source: SourceId,
 }
 
 impl Symbol {
     pub fn new(string: String, cursor: Cursor) -> Self {
         let len: u64;
-        match string.len() {
+        // Cursors count characters, not bytes
+        match string.chars().count() {
             0 => {
                 len = 1;
             },
-            _ => {
-                len = string.len() as u64;
+            count => {
+                len = count as u64;
             }
         }
         let start = Cursor {
-            column: cursor.column - (len - 1),
-            pos: cursor.pos - (len - 1),
+            column: cursor.column.saturating_sub(len - 1),
+            pos: cursor.pos.saturating_sub(len - 1),
             line: cursor.line
         };
         Self {
             symbol: string,
             start,
             end: cursor,
+            source: SourceId::default(),
         } 
     }
     pub fn len(self: &Self) -> usize {
@@ -88,6 +94,8 @@ pub enum TokenTypes {
     LiteralFloat(f64),
     LiteralInt(i64),
     LiteralString(String),
+    LiteralChar(char),
+    LiteralBool(bool),
     Function(String),
     Variable(String),
     Struct(String),
@@ -100,6 +108,37 @@ pub enum TokenTypes {
     Root,
     Return,
     ShortReturn,
+    If,
+    Condition,
+    Loop,
+    Break,
+    Binary(Operators),
+    Unary(Operators),
+    Group,
+    /// `{ id: 69, name: "John" }`, its struct comes from where it is used
+    StructLiteral,
+    /// A field being set in a struct literal
+    Field(String),
+    /// Reading a field, `employee.id`, or calling a method when it has
+    /// parameters, `employee.nameplate()`
+    Access(String),
+    /// A function of a struct, `person:new()`
+    Static(String),
+    /// `employee.nameplate()`, the first child is the receiver
+    MethodCall(String),
+    /// `list[0]`, the first child is what is being indexed
+    Index,
+    /// `["Hello", "World"]`
+    VecLiteral,
+    /// `a~flt`, the first child is the value and the last one its new type
+    Cast,
+    /// `<< #multiply ...`, the declaration in it can be imported by other
+    /// files
+    Export,
+    /// `#multiply@dep.nl`, the declaration comes from another file
+    Import,
+    /// A piece of the path of an import, `dep`, `.` and `nl`
+    Path(String),
 }
 
 impl Default for TokenTypes {
@@ -119,12 +158,13 @@ impl Display for TokenTypes {
             TokenTypes::LiteralFloat(flt) => format!("LitFlt({})", flt),
             TokenTypes::LiteralInt(int) => format!("LitInt({})", int),
             TokenTypes::LiteralString(string) => format!("LitStr({})", string),
+            TokenTypes::LiteralChar(chr) => format!("LitChr({})", chr),
+            TokenTypes::LiteralBool(bol) => format!("LitBol({})", bol),
             TokenTypes::Function(identifier) => format!("Fun({})", identifier),
             TokenTypes::Variable(var) => format!("Var({})", var),
             TokenTypes::Call(function) => format!("Call({})", function),
-            // TokenTypes::Struct(_) => todo!(),
-            // TokenTypes::Implement => todo!(),
             TokenTypes::CallParams => "CallParams".to_string(),
+            TokenTypes::Separator => "Separator".to_string(),
             TokenTypes::Generic => "Generic".to_string(),
             TokenTypes::Body => "Body".to_string(),
             TokenTypes::Statement => "Statement".to_string(),
@@ -132,6 +172,26 @@ impl Display for TokenTypes {
             TokenTypes::Root => "Root".to_string(),
             TokenTypes::Return => "Return".to_string(),
             TokenTypes::ShortReturn => "ShortReturn".to_string(),
+            TokenTypes::If => "If".to_string(),
+            TokenTypes::Condition => "Condition".to_string(),
+            TokenTypes::Loop => "Loop".to_string(),
+            TokenTypes::Break => "Break".to_string(),
+            TokenTypes::Binary(operator) => format!("Binary({})", operator),
+            TokenTypes::Unary(operator) => format!("Unary({})", operator),
+            TokenTypes::Group => "Group".to_string(),
+            TokenTypes::Struct(name) => format!("Struct({})", name),
+            TokenTypes::StructLiteral => "StructLiteral".to_string(),
+            TokenTypes::Field(field) => format!("Field({})", field),
+            TokenTypes::Access(field) => format!("Access({})", field),
+            TokenTypes::Static(function) => format!("Static({})", function),
+            TokenTypes::MethodCall(method) => format!("MethodCall({})", method),
+            TokenTypes::Implement => "Implement".to_string(),
+            TokenTypes::Index => "Index".to_string(),
+            TokenTypes::VecLiteral => "VecLiteral".to_string(),
+            TokenTypes::Cast => "Cast".to_string(),
+            TokenTypes::Export => "Export".to_string(),
+            TokenTypes::Import => "Import".to_string(),
+            TokenTypes::Path(path) => format!("Path({})", path),
             _ => "Unkown".to_string()
 })
     }
@@ -140,12 +200,16 @@ impl Display for TokenTypes {
 #[derive(Debug, PartialEq, Clone)]
 pub enum DataTypes {
     Unknown,
+    /// What functions without a return type return
+    Void,
     Int,
     Flt,
     Bol,
     Chr,
     Str,
     Vec,
+    /// The struct a function belongs to
+    Slf,
     User(String)
 }
 
@@ -159,6 +223,7 @@ impl TryFrom<String> for DataTypes {
             "chr" => Ok(Self::Chr),
             "str" => Ok(Self::Str),
             "vec" => Ok(Self::Vec),
+            "self" => Ok(Self::Slf),
             _ => Err("Not a basic type")
             // _ => Ok(Self::User(symbol))
         }
@@ -177,6 +242,56 @@ impl Display for DataTypes {
     }
 }
 
+/// A full type, `vec<str>` is a `Vec` with `Str` as its only generic
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TypeSignature {
+    pub data_type: DataTypes,
+    pub generics: Vec<TypeSignature>,
+}
+
+impl TypeSignature {
+    pub fn new(data_type: DataTypes) -> Self {
+        Self {
+            data_type,
+            generics: vec![],
+        }
+    }
+    pub fn void() -> Self {
+        Self::new(DataTypes::Void)
+    }
+    /// Types that could not be resolved are compatible with everything so
+    /// a single mistake is only reported once
+    pub fn is_unknown(self: &Self) -> bool {
+        return self.data_type == DataTypes::Unknown;
+    }
+    pub fn is_numeric(self: &Self) -> bool {
+        matches!(self.data_type, DataTypes::Int | DataTypes::Flt)
+    }
+}
+
+impl Display for TypeSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match &self.data_type {
+            DataTypes::Unknown => "{unknown}",
+            DataTypes::Void => "void",
+            DataTypes::Int => "int",
+            DataTypes::Flt => "flt",
+            DataTypes::Bol => "bol",
+            DataTypes::Chr => "chr",
+            DataTypes::Str => "str",
+            DataTypes::Vec => "vec",
+            DataTypes::Slf => "self",
+            DataTypes::User(name) => name.as_str(),
+        };
+        write!(f, "{}", name)?;
+        // Written the way the source writes them, `map<str><int>`
+        for generic in &self.generics {
+            write!(f, "<{}>", generic)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Operators {
     Ass, // Assignment
@@ -194,6 +309,7 @@ pub enum Operators {
     AssAdd, // +=
     AssSub, // -=
     Equ, // Equality
+    NotEqu,
     EquGt,
     EquLt,
     BitLeft,
@@ -219,6 +335,7 @@ impl TryFrom<String> for Operators {
             "+=" => Ok(Self::AssAdd),
             "-=" => Ok(Self::AssSub),
             "==" => Ok(Self::Equ),
+            "!=" => Ok(Self::NotEqu),
             ">=" => Ok(Self::EquGt),
             "<=" => Ok(Self::EquLt),
             "<<" => Ok(Self::BitLeft),
@@ -228,6 +345,34 @@ impl TryFrom<String> for Operators {
     }
 }
 
+impl Operators {
+    /// How the operator is written in the source
+    pub fn symbol(self: &Self) -> &'static str {
+        match self {
+            Self::Ass => "=",
+            Self::Mul => "*",
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Div => "/",
+            Self::Mod => "%",
+            Self::Lt => "<",
+            Self::Gt => ">",
+            Self::BitOr => "|",
+            Self::BitAnd => "&",
+            Self::AddAdd => "++",
+            Self::SubSub => "--",
+            Self::AssAdd => "+=",
+            Self::AssSub => "-=",
+            Self::Equ => "==",
+            Self::NotEqu => "!=",
+            Self::EquGt => ">=",
+            Self::EquLt => "<=",
+            Self::BitLeft => "<<",
+            Self::BitRight => ">>",
+        }
+    }
+}
+
 impl Display for Operators {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
@@ -257,53 +402,65 @@ impl Display for Cursor {
     }
 }
 
+/// Name under which the function of a struct is compiled,
+/// `#nameplate^person` is `nameplate^person`
+pub fn method_name(function: &str, structure: &str) -> String {
+    format!("{}^{}", function, structure)
+}
+
+/// Variables visible from a point of the program. Every block opens a
+/// new level where variables of the outer levels can be shadowed.
+#[derive(Debug)]
 pub struct Scope {
-    pub functions: Vec<String>,
-    pub variables: Vec<String>,
+    /// Name and id of the declaring node of each variable, by block
+    blocks: Vec<Vec<(String, u64)>>,
 }
 
 impl Default for Scope {
     fn default() -> Self {
         Self {
-            functions: vec![],
-            variables: vec![]
+            blocks: vec![vec![]],
         }
     }
 }
 
-type ScopeError = &'static str;
-
 impl Scope {
-    pub fn function_exists(
-        self: &Self,
-        function: &String,
-    ) -> bool {
-        self.functions.contains(&function)
+    pub fn push(self: &mut Self) {
+        self.blocks.push(vec![]);
+    }
+    pub fn pop(self: &mut Self) {
+        self.blocks.pop();
     }
-    pub fn insert_function(
+    /// Fails with the previous declaration when the variable was already
+    /// declared in the same block
+    pub fn declare(
         self: &mut Self,
-        function: String
-    ) -> Result<(), ScopeError> {
-        if self.functions.contains(&function) {
-            return Err("Function was already defined");
+        variable: String,
+        node: u64,
+    ) -> Result<(), u64> {
+        let block = match self.blocks.last_mut() {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+        if let Some((_, previous)) = block.iter().find(|(name, _)| *name == variable) {
+            return Err(*previous);
         }
-        self.functions.push(function);
+        block.push((variable, node));
         return Ok(())
     }
-    pub fn variable_exists(
+    /// The declaring node of the closest variable with that name
+    pub fn lookup(
         self: &Self,
-        variable: &String,
-    ) -> bool {
-        self.variables.contains(&variable)
-    }
-    pub fn insert_variable(
-        self: &mut Self,
-        variable: String
-    ) -> Result<(), ScopeError> {
-        if self.variable_exists(&variable) {
-            return Ok(())
-        }
-        self.variables.push(variable);
-        return Ok(())
+        variable: &str,
+    ) -> Option<u64> {
+        self.blocks
+            .iter()
+            .rev()
+            .find_map(|block| block
+                .iter()
+                .rev()
+                .find(|(name, _)| name == variable)
+                .map(|(_, node)| *node)
+            )
     }
-}
\ No newline at end of file
+}
@@ -1,4 +1,4 @@
-use std::{fs::File, io::{Read, SeekFrom, Seek}, fmt::Display};
+use std::io::{self, BufReader, Read};
 
 pub fn get_index(vector: &Vec<String>, key: &str) -> i32 {
     let lookup = vector.iter().position(|v| v == key);
@@ -141,70 +141,57 @@ pub fn get_char_length(buf: [u8; 4]) -> u8 {
 }
 
 
-pub struct CharReader {
+/// Characters of a source, it is read whole or a character at a time with
+/// `low_mem`
+pub struct CharReader<R: Read> {
     low_mem: bool,
-    file: File,
-    file_contents: Option<Vec<char>>,
+    reader: BufReader<R>,
+    contents: Vec<char>,
     current_pos: usize,
-    file_length: usize,
 }
 
-impl CharReader {
-    pub fn new(mut file: File, low_mem: Option<bool>) -> Self {
-        let mut file_contents = None;
-        let mut buf = String::new();
-        if !(low_mem.unwrap_or(false)) {
-            file.read_to_string(&mut buf)
-                .expect("Cannot read file contents");
-            let chars = buf.chars();
-            let chars = chars.collect();
-            file_contents = Some(chars);
+impl<R: Read> CharReader<R> {
+    pub fn new(reader: R, low_mem: Option<bool>) -> io::Result<Self> {
+        let low_mem = low_mem.unwrap_or(false);
+        let mut reader = BufReader::new(reader);
+        let mut contents = vec![];
+        if !low_mem {
+            let mut buf = String::new();
+            reader.read_to_string(&mut buf)?;
+            contents = buf.chars().collect();
         }
-        // Get the file length
-        let seek = file.seek(SeekFrom::End(0)).unwrap();
-        Self {
-            low_mem: low_mem.unwrap_or(false), 
-            file, 
-            file_contents,
+        Ok(Self {
+            low_mem,
+            reader,
+            contents,
             current_pos: 0,
-            file_length: seek as usize,
-        }
+        })
     }
     fn read(&mut self) -> Option<char> {
-        let next = self.file_contents.as_ref().unwrap()
-            .get(self.current_pos);
+        let next = self.contents.get(self.current_pos).copied();
         self.current_pos += 1;
-        match next {
-            Some(next) => {
-                Some(*next)
-            },
-            None => {
-                None
-            },
-        }
+        next
     }
+    /// Bytes that are not UTF-8 are read as the replacement character
     fn read_low_mem(&mut self) -> Option<char> {
         let mut buf = [0; 4];
-        if self.file_length <= self.current_pos {
-            return None
+        self.reader.read_exact(&mut buf[..1]).ok()?;
+        let char_length = match buf[0].leading_ones() {
+            0 => 1,
+            length @ 2..=4 => length as usize,
+            _ => return Some(char::REPLACEMENT_CHARACTER),
+        };
+        if self.reader.read_exact(&mut buf[1..char_length]).is_err() {
+            return Some(char::REPLACEMENT_CHARACTER);
         }
-        self.file.seek(SeekFrom::Start(self.current_pos as u64))
-            .expect(format!("Could not read file, attempted to seek to: {}", self.current_pos).as_str());
-        let _bytes_read = self.file.read(&mut buf[..])
-            .expect("Could not read file");
-        let char_length = get_char_length(buf) as usize;
-        self.current_pos += char_length;
-        let buf = [
-            buf[0],
-            if char_length > 1 {buf[1]} else {0},
-            if char_length > 2 {buf[2]} else {0},
-            if char_length > 3 {buf[3]} else {0},
-        ];
-        char::from_u32(u32::from_le_bytes(buf))
+        let character = std::str::from_utf8(&buf[..char_length])
+            .ok()
+            .and_then(|character| character.chars().next());
+        Some(character.unwrap_or(char::REPLACEMENT_CHARACTER))
     }
 }
 
-impl Iterator for CharReader {
+impl<R: Read> Iterator for CharReader<R> {
     type Item = char;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -214,4 +201,4 @@ impl Iterator for CharReader {
             self.read()
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,681 @@
+use std::fmt::Display;
+use std::rc::Rc;
+
+use hashbrown::HashMap;
+
+use super::bytecode::{Casts, OpCodes, Program};
+use super::core::Natives;
+use super::types::Cursor;
+
+/// Values living in the stack of the virtual machine
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Void,
+    Int(i64),
+    Flt(f64),
+    Bol(bool),
+    Chr(char),
+    Str(Rc<str>),
+    Vec(Rc<Vec<Value>>),
+    /// Fields in the order they were declared and the kind of the struct
+    /// when it extends another one, see `Program::kinds`
+    Struct(Rc<Vec<Value>>, Option<u32>),
+    Map(Rc<Map>),
+}
+
+/// Values that can be the keys of a map
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    Int(i64),
+    Bol(bool),
+    Chr(char),
+    Str(Rc<str>),
+}
+
+impl Key {
+    pub fn new(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Int(int) => Ok(Self::Int(*int)),
+            Value::Bol(bol) => Ok(Self::Bol(*bol)),
+            Value::Chr(chr) => Ok(Self::Chr(*chr)),
+            Value::Str(string) => Ok(Self::Str(string.clone())),
+            value => Err(format!("A {} cannot be the key of a map", value.type_name())),
+        }
+    }
+    pub fn value(self: &Self) -> Value {
+        match self {
+            Self::Int(int) => Value::Int(*int),
+            Self::Bol(bol) => Value::Bol(*bol),
+            Self::Chr(chr) => Value::Chr(*chr),
+            Self::Str(string) => Value::Str(string.clone()),
+        }
+    }
+}
+
+/// Keys keep the order they were first set in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Map {
+    values: HashMap<Key, Value>,
+    order: Vec<Key>,
+}
+
+impl Map {
+    pub fn get(self: &Self, key: &Key) -> Option<&Value> {
+        self.values.get(key)
+    }
+    pub fn set(self: &mut Self, key: Key, value: Value) {
+        if self.values.insert(key.clone(), value).is_none() {
+            self.order.push(key);
+        }
+    }
+    pub fn remove(self: &mut Self, key: &Key) {
+        if self.values.remove(key).is_some() {
+            self.order.retain(|existing| existing != key);
+        }
+    }
+    pub fn keys(self: &Self) -> &[Key] {
+        &self.order
+    }
+}
+
+impl Default for Value {
+    fn default() -> Self {
+        return Self::Void;
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Void => write!(f, "void"),
+            Value::Int(int) => write!(f, "{}", int),
+            Value::Flt(flt) => write!(f, "{}", flt),
+            Value::Bol(bol) => write!(f, "{}", bol),
+            Value::Chr(chr) => write!(f, "{}", chr),
+            Value::Str(string) => write!(f, "{}", string),
+            Value::Vec(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::Map(map) => {
+                write!(f, "{{")?;
+                for (index, key) in map.keys().iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key.value(), map.get(key).cloned().unwrap_or_default())?;
+                }
+                write!(f, "}}")
+            }
+            Value::Struct(fields, _) => {
+                write!(f, "{{")?;
+                for (index, field) in fields.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", field)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl Value {
+    pub fn type_name(self: &Self) -> &'static str {
+        match self {
+            Value::Void => "void",
+            Value::Int(_) => "int",
+            Value::Flt(_) => "flt",
+            Value::Bol(_) => "bol",
+            Value::Chr(_) => "chr",
+            Value::Str(_) => "str",
+            Value::Vec(_) => "vec",
+            Value::Struct(_, _) => "struct",
+            Value::Map(_) => "map",
+        }
+    }
+
+    /// Converts the value for `value~type`, failing when it has no value of
+    /// that type, `"abc"~int`
+    pub fn cast(self: &Self, cast: Casts) -> Result<Value, String> {
+        let converted = match (self, cast) {
+            (Value::Int(_), Casts::Int)
+            | (Value::Flt(_), Casts::Flt)
+            | (Value::Bol(_), Casts::Bol)
+            | (Value::Chr(_), Casts::Chr)
+            | (Value::Str(_), Casts::Str) => Some(self.clone()),
+            (Value::Int(int), Casts::Flt) => Some(Value::Flt(*int as f64)),
+            (Value::Int(int), Casts::Bol) => Some(Value::Bol(*int != 0)),
+            (Value::Int(int), Casts::Chr) => u32::try_from(*int)
+                .ok()
+                .and_then(char::from_u32)
+                .map(Value::Chr),
+            // Decimals are dropped, `2.9~int` is `2`
+            (Value::Flt(flt), Casts::Int) if flt.is_finite()
+                && *flt >= i64::MIN as f64
+                && *flt < i64::MAX as f64 => Some(Value::Int(*flt as i64)),
+            (Value::Flt(_), Casts::Int) => None,
+            (Value::Bol(bol), Casts::Int) => Some(Value::Int(*bol as i64)),
+            (Value::Chr(chr), Casts::Int) => Some(Value::Int(*chr as i64)),
+            (Value::Str(string), Casts::Int) => string.parse().ok().map(Value::Int),
+            (Value::Str(string), Casts::Flt) => string.parse().ok().map(Value::Flt),
+            (Value::Str(string), Casts::Bol) => match string.as_ref() {
+                "true" => Some(Value::Bol(true)),
+                "false" => Some(Value::Bol(false)),
+                _ => None,
+            },
+            (Value::Str(string), Casts::Chr) => {
+                let mut chars = string.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(chr), None) => Some(Value::Chr(chr)),
+                    _ => None,
+                }
+            }
+            (value, Casts::Str) => Some(Value::Str(value.to_string().into())),
+            _ => return Err(format!(
+                "Cannot cast a {} to {}", self.type_name(), cast
+            )),
+        };
+        converted.ok_or_else(|| match self {
+            Value::Str(string) => format!("Cannot cast \"{}\" to {}", string, cast),
+            value => format!("Cannot cast {} to {}", value, cast),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct RuntimeError {
+    pub message: String,
+    /// Function that was running when the error happened
+    pub function: String,
+    pub cursor: Option<Cursor>,
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.cursor {
+            Some(cursor) => write!(
+                f, "{} in #{} at {}:{}",
+                self.message, self.function, cursor.line, cursor.column
+            ),
+            None => write!(f, "{} in #{}", self.message, self.function),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Frame {
+    function: usize,
+    /// Index of the next instruction
+    pc: usize,
+    /// Position of the first local of this frame in the stack
+    base: usize,
+}
+
+pub struct Vm {
+    program: Program,
+    /// Functions the program calls with `CallNative`
+    natives: Natives,
+    stack: Vec<Value>,
+    frames: Vec<Frame>,
+}
+
+/// Amount of nested calls before the program is stopped
+const MAX_FRAMES: usize = 4096;
+
+impl Vm {
+    pub fn new(program: Program) -> Self {
+        Self::with_natives(program, super::core::register())
+    }
+
+    /// Runs the program with other native functions than the ones of the
+    /// runtime, they have to be the ones it was checked with
+    pub fn with_natives(program: Program, natives: Natives) -> Self {
+        Self {
+            program,
+            natives,
+            stack: vec![],
+            frames: vec![],
+        }
+    }
+
+    /// Runs the #main function of the program with the arguments given and
+    /// returns the exit code of the program
+    pub fn run(self: &mut Self, args: Vec<String>) -> Result<i32, RuntimeError> {
+        let entry = self.program.entry
+            .ok_or_else(|| RuntimeError {
+                message: "Program has no #main function".to_string(),
+                function: "main".to_string(),
+                cursor: None,
+            })?;
+        let params = self.program.functions
+            .get(entry as usize)
+            .ok_or_else(|| self.error(format!("Unknown function {}", entry)))?
+            .params;
+        let args = Value::Vec(Rc::new(
+            args.into_iter().map(|arg| Value::Str(arg.into())).collect()
+        ));
+        // #main may or may not take the arguments
+        let args = if params > 0 { vec![args] } else { vec![] };
+        let result = self.call(entry, args)?;
+        match result {
+            Value::Int(code) => Ok(code as i32),
+            _ => Ok(0),
+        }
+    }
+
+    /// Calls the function at `index` with the arguments and runs until it
+    /// returns
+    pub fn call(
+        self: &mut Self,
+        index: u32,
+        args: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let depth = self.frames.len();
+        let function = self.program.functions.get(index as usize)
+            .ok_or_else(|| self.error(format!("Unknown function {}", index)))?;
+        if args.len() != function.params as usize {
+            return Err(RuntimeError {
+                message: format!("Function expects {} arguments, got {}", function.params, args.len()),
+                function: function.name.clone(),
+                cursor: None,
+            });
+        }
+        // What the failed call left behind is dropped so the vm can be
+        // called again
+        let base = self.stack.len();
+        self.stack.extend(args);
+        let result = self.push_frame(index as usize).and_then(|_| self.execute(depth));
+        if result.is_err() {
+            self.frames.truncate(depth);
+            self.stack.truncate(base);
+        }
+        result
+    }
+
+    /// Index of the function that `call` takes, `nameplate^person` for the
+    /// functions of a struct
+    pub fn function_index(self: &Self, name: &str) -> Option<u32> {
+        self.program.function_index(name)
+    }
+
+    fn push_frame(self: &mut Self, function: usize) -> Result<(), RuntimeError> {
+        if self.frames.len() >= MAX_FRAMES {
+            return Err(self.error("Stack overflow".to_string()));
+        }
+        let code = &self.program.functions[function];
+        let base = match self.stack.len().checked_sub(code.params as usize) {
+            Some(base) => base,
+            None => return Err(self.error("Stack underflow".to_string())),
+        };
+        // Make room for the locals that are not parameters
+        let extra = code.locals.saturating_sub(code.params as u32) as usize;
+        self.stack.resize(self.stack.len() + extra, Value::Void);
+        self.frames.push(Frame {
+            function,
+            pc: 0,
+            base,
+        });
+        Ok(())
+    }
+
+    /// Dispatch loop, runs until the frame count goes back to `depth`
+    fn execute(self: &mut Self, depth: usize) -> Result<Value, RuntimeError> {
+        loop {
+            let frame = self.frames.last_mut().unwrap();
+            let function = &self.program.functions[frame.function];
+            let op = match function.code.get(frame.pc) {
+                Some(op) => op.clone(),
+                None => return Err(self.error("Function ended without returning".to_string())),
+            };
+            frame.pc += 1;
+            let base = frame.base;
+            // Parameters are the first locals
+            let locals = function.locals.max(function.params as u32);
+            match op {
+                OpCodes::PushInt(int) => self.stack.push(Value::Int(int)),
+                OpCodes::PushFlt(flt) => self.stack.push(Value::Flt(flt)),
+                OpCodes::PushBol(bol) => self.stack.push(Value::Bol(bol)),
+                OpCodes::PushStr(index) => {
+                    let string = self.string(index)?;
+                    self.stack.push(Value::Str(string.into()));
+                }
+                OpCodes::PushVoid => self.stack.push(Value::Void),
+                OpCodes::PushChr(chr) => self.stack.push(Value::Chr(chr)),
+                OpCodes::Load(slot) => {
+                    let value = match self.stack.get(base + slot as usize) {
+                        Some(value) if slot < locals => value.clone(),
+                        _ => return Err(self.error(format!("Unknown local {}", slot))),
+                    };
+                    self.stack.push(value);
+                }
+                OpCodes::Store(slot) => {
+                    let value = self.pop()?;
+                    match self.stack.get_mut(base + slot as usize) {
+                        Some(local) if slot < locals => *local = value,
+                        _ => return Err(self.error(format!("Unknown local {}", slot))),
+                    }
+                }
+                OpCodes::Pop => {
+                    self.pop()?;
+                }
+                OpCodes::Add
+                | OpCodes::Sub
+                | OpCodes::Mul
+                | OpCodes::Div
+                | OpCodes::Mod
+                | OpCodes::Lt
+                | OpCodes::Gt
+                | OpCodes::Equ
+                | OpCodes::NotEqu
+                | OpCodes::EquGt
+                | OpCodes::EquLt
+                | OpCodes::BitOr
+                | OpCodes::BitAnd
+                | OpCodes::BitLeft
+                | OpCodes::BitRight => {
+                    let right = self.pop()?;
+                    let left = self.pop()?;
+                    let result = binary(&op, left, right)
+                        .map_err(|message| self.error(message))?;
+                    self.stack.push(result);
+                }
+                OpCodes::Neg => {
+                    let value = match self.pop()? {
+                        Value::Int(int) => Value::Int(int.wrapping_neg()),
+                        Value::Flt(flt) => Value::Flt(-flt),
+                        value => return Err(self.error(format!(
+                            "Cannot negate {}", value.type_name()
+                        ))),
+                    };
+                    self.stack.push(value);
+                }
+                OpCodes::Jump(target) => {
+                    self.frames.last_mut().unwrap().pc = target as usize;
+                }
+                OpCodes::JumpIfFalse(target) => {
+                    match self.pop()? {
+                        Value::Bol(true) => {}
+                        Value::Bol(false) => {
+                            self.frames.last_mut().unwrap().pc = target as usize;
+                        }
+                        value => return Err(self.error(format!(
+                            "Expected a bol in the condition, got {}", value.type_name()
+                        ))),
+                    }
+                }
+                OpCodes::Call(index, args) => self.call_function(index, args)?,
+                // The kind of the receiver can replace the function
+                OpCodes::CallMethod(method, index, args) => {
+                    let at = self.stack.len().checked_sub(args as usize)
+                        .ok_or_else(|| self.error("Stack underflow".to_string()))?;
+                    let index = match self.stack.get(at) {
+                        Some(Value::Struct(_, Some(kind))) => self.program.kinds
+                            .get(*kind as usize)
+                            .and_then(|kind| kind.methods.iter().find(|(name, _)| *name == method))
+                            .map_or(index, |(_, function)| *function),
+                        _ => index,
+                    };
+                    self.call_function(index, args)?;
+                }
+                OpCodes::CallNative(name, args) => {
+                    let name = self.string(name)?;
+                    let at = self.stack.len().checked_sub(args as usize)
+                        .ok_or_else(|| self.error("Stack underflow".to_string()))?;
+                    let args = self.stack.split_off(at);
+                    let result = self.natives.call(&name, &args)
+                        .map_err(|message| self.error(message))?;
+                    self.stack.push(result);
+                }
+                OpCodes::MakeStruct(fields) => {
+                    let at = self.stack.len().checked_sub(fields as usize)
+                        .ok_or_else(|| self.error("Stack underflow".to_string()))?;
+                    let fields = self.stack.split_off(at);
+                    self.stack.push(Value::Struct(Rc::new(fields), None));
+                }
+                OpCodes::GetField(index) => {
+                    let value = match self.pop()? {
+                        Value::Struct(fields, _) => fields.get(index as usize).cloned(),
+                        value => return Err(self.error(format!(
+                            "Cannot read a field of {}", value.type_name()
+                        ))),
+                    };
+                    let value = value.ok_or_else(|| self.error(format!(
+                        "Struct has no field {}", index
+                    )))?;
+                    self.stack.push(value);
+                }
+                OpCodes::SetField(index) => {
+                    let value = self.pop()?;
+                    let (mut fields, kind) = match self.pop()? {
+                        Value::Struct(fields, kind) => (fields, kind),
+                        value => return Err(self.error(format!(
+                            "Cannot set a field of {}", value.type_name()
+                        ))),
+                    };
+                    match Rc::make_mut(&mut fields).get_mut(index as usize) {
+                        Some(field) => *field = value,
+                        None => return Err(self.error(format!("Struct has no field {}", index))),
+                    }
+                    self.stack.push(Value::Struct(fields, kind));
+                }
+                OpCodes::SetKind(kind) => {
+                    let fields = match self.pop()? {
+                        Value::Struct(fields, _) => fields,
+                        value => return Err(self.error(format!(
+                            "Cannot give a kind to {}", value.type_name()
+                        ))),
+                    };
+                    self.stack.push(Value::Struct(fields, Some(kind)));
+                }
+                OpCodes::MakeVec(items) => {
+                    let at = self.stack.len().checked_sub(items as usize)
+                        .ok_or_else(|| self.error("Stack underflow".to_string()))?;
+                    let items = self.stack.split_off(at);
+                    self.stack.push(Value::Vec(Rc::new(items)));
+                }
+                OpCodes::GetIndex => {
+                    let index = self.pop()?;
+                    let value = self.pop()?;
+                    let item = match (&value, &index) {
+                        (Value::Vec(items), Value::Int(index)) => usize::try_from(*index)
+                            .ok()
+                            .and_then(|index| items.get(index).cloned())
+                            .ok_or_else(|| self.error(format!(
+                                "Index {} is out of bounds for a vec of {} items",
+                                index, items.len()
+                            )))?,
+                        (Value::Str(string), Value::Int(index)) => usize::try_from(*index)
+                            .ok()
+                            .and_then(|index| string.chars().nth(index))
+                            .map(Value::Chr)
+                            .ok_or_else(|| self.error(format!(
+                                "Index {} is out of bounds for a str of {} characters",
+                                index, string.chars().count()
+                            )))?,
+                        _ => return Err(self.error(format!(
+                            "Cannot index into {} with {}",
+                            value.type_name(), index.type_name()
+                        ))),
+                    };
+                    self.stack.push(item);
+                }
+                OpCodes::MakeMap(pairs) => {
+                    let at = self.stack.len().checked_sub(pairs as usize * 2)
+                        .ok_or_else(|| self.error("Stack underflow".to_string()))?;
+                    let items = self.stack.split_off(at);
+                    let mut map = Map::default();
+                    for pair in items.chunks(2) {
+                        let key = Key::new(&pair[0]).map_err(|message| self.error(message))?;
+                        map.set(key, pair[1].clone());
+                    }
+                    self.stack.push(Value::Map(Rc::new(map)));
+                }
+                OpCodes::GetKey(index) => {
+                    let key = Key::Str(self.string(index)?.into());
+                    let value = match self.pop()? {
+                        Value::Map(map) => map.get(&key).cloned(),
+                        value => return Err(self.error(format!(
+                            "Cannot read a key of {}", value.type_name()
+                        ))),
+                    };
+                    let value = value.ok_or_else(|| self.error(format!(
+                        "Map has no key `{}`", key.value()
+                    )))?;
+                    self.stack.push(value);
+                }
+                OpCodes::Cast(cast) => {
+                    let value = self.pop()?;
+                    let value = value.cast(cast).map_err(|message| self.error(message))?;
+                    self.stack.push(value);
+                }
+                OpCodes::Return => {
+                    let result = self.pop()?;
+                    let frame = self.frames.pop().unwrap();
+                    self.stack.truncate(frame.base);
+                    if self.frames.len() <= depth {
+                        return Ok(result);
+                    }
+                    self.stack.push(result);
+                }
+            }
+        }
+    }
+
+    fn call_function(self: &mut Self, index: u32, args: u8) -> Result<(), RuntimeError> {
+        if index as usize >= self.program.functions.len() {
+            return Err(self.error(format!("Unknown function {}", index)));
+        }
+        let params = self.program.functions[index as usize].params;
+        if params != args {
+            return Err(self.error(format!(
+                "Function expects {} arguments, got {}", params, args
+            )));
+        }
+        self.push_frame(index as usize)
+    }
+
+    fn pop(self: &mut Self) -> Result<Value, RuntimeError> {
+        match self.stack.pop() {
+            Some(value) => Ok(value),
+            None => Err(self.error("Stack underflow".to_string())),
+        }
+    }
+
+    fn string(self: &Self, index: u32) -> Result<String, RuntimeError> {
+        self.program.strings.get(index as usize)
+            .cloned()
+            .ok_or_else(|| self.error(format!("Unknown string {}", index)))
+    }
+
+    /// Creates an error pointing at the instruction currently running
+    fn error(self: &Self, message: String) -> RuntimeError {
+        match self.frames.last() {
+            Some(frame) => {
+                let function = &self.program.functions[frame.function];
+                RuntimeError {
+                    message,
+                    function: function.name.clone(),
+                    // pc already moved past the instruction
+                    cursor: function.cursors
+                        .get(frame.pc.saturating_sub(1))
+                        .copied(),
+                }
+            }
+            None => RuntimeError {
+                message,
+                function: String::new(),
+                cursor: None,
+            },
+        }
+    }
+}
+
+fn binary(op: &OpCodes, left: Value, right: Value) -> Result<Value, String> {
+    let operator = match op.symbol() {
+        Some(symbol) => format!("`{}`", symbol),
+        None => op.to_string(),
+    };
+    let mismatch = |left: &Value, right: &Value| format!(
+        "Cannot apply {} to {} and {}", operator, left.type_name(), right.type_name()
+    );
+    Ok(match (&left, &right) {
+        (Value::Int(l), Value::Int(r)) => {
+            let (l, r) = (*l, *r);
+            match op {
+                OpCodes::Add => Value::Int(l.wrapping_add(r)),
+                OpCodes::Sub => Value::Int(l.wrapping_sub(r)),
+                OpCodes::Mul => Value::Int(l.wrapping_mul(r)),
+                OpCodes::Div | OpCodes::Mod if r == 0 => {
+                    return Err("Division by zero".to_string());
+                }
+                OpCodes::Div => Value::Int(l.wrapping_div(r)),
+                OpCodes::Mod => Value::Int(l.wrapping_rem(r)),
+                OpCodes::Lt => Value::Bol(l < r),
+                OpCodes::Gt => Value::Bol(l > r),
+                OpCodes::Equ => Value::Bol(l == r),
+                OpCodes::NotEqu => Value::Bol(l != r),
+                OpCodes::EquGt => Value::Bol(l >= r),
+                OpCodes::EquLt => Value::Bol(l <= r),
+                OpCodes::BitOr => Value::Int(l | r),
+                OpCodes::BitAnd => Value::Int(l & r),
+                OpCodes::BitLeft => Value::Int(l.wrapping_shl(r as u32)),
+                OpCodes::BitRight => Value::Int(l.wrapping_shr(r as u32)),
+                _ => return Err(mismatch(&left, &right)),
+            }
+        }
+        (Value::Flt(_) | Value::Int(_), Value::Flt(_) | Value::Int(_)) => {
+            let l = as_flt(&left);
+            let r = as_flt(&right);
+            match op {
+                OpCodes::Add => Value::Flt(l + r),
+                OpCodes::Sub => Value::Flt(l - r),
+                OpCodes::Mul => Value::Flt(l * r),
+                OpCodes::Div => Value::Flt(l / r),
+                OpCodes::Mod => Value::Flt(l % r),
+                OpCodes::Lt => Value::Bol(l < r),
+                OpCodes::Gt => Value::Bol(l > r),
+                OpCodes::Equ => Value::Bol(l == r),
+                OpCodes::NotEqu => Value::Bol(l != r),
+                OpCodes::EquGt => Value::Bol(l >= r),
+                OpCodes::EquLt => Value::Bol(l <= r),
+                _ => return Err(mismatch(&left, &right)),
+            }
+        }
+        (Value::Chr(l), Value::Chr(r)) => match op {
+            OpCodes::Lt => Value::Bol(l < r),
+            OpCodes::Gt => Value::Bol(l > r),
+            OpCodes::Equ => Value::Bol(l == r),
+            OpCodes::NotEqu => Value::Bol(l != r),
+            OpCodes::EquGt => Value::Bol(l >= r),
+            OpCodes::EquLt => Value::Bol(l <= r),
+            _ => return Err(mismatch(&left, &right)),
+        },
+        (Value::Str(l), Value::Str(r)) => match op {
+            OpCodes::Add => Value::Str(format!("{}{}", l, r).into()),
+            OpCodes::Equ => Value::Bol(l == r),
+            OpCodes::NotEqu => Value::Bol(l != r),
+            _ => return Err(mismatch(&left, &right)),
+        },
+        _ => match op {
+            OpCodes::Equ => Value::Bol(left == right),
+            OpCodes::NotEqu => Value::Bol(left != right),
+            _ => return Err(mismatch(&left, &right)),
+        },
+    })
+}
+
+fn as_flt(value: &Value) -> f64 {
+    match value {
+        Value::Int(int) => *int as f64,
+        Value::Flt(flt) => *flt,
+        _ => 0.0,
+    }
+}
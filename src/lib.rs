@@ -1 +1,26 @@
-pub mod engine;
\ No newline at end of file
+// The engine is written with explicit returns, `self: &Self` receivers and
+// `match` blocks that are expected to grow more arms
+#![allow(
+    clippy::needless_return,
+    clippy::needless_arbitrary_self_type,
+    clippy::redundant_static_lifetimes,
+    clippy::single_match,
+    clippy::derivable_impls,
+    clippy::clone_on_copy,
+    clippy::len_zero,
+    clippy::needless_borrow,
+    clippy::needless_late_init,
+    clippy::while_let_on_iterator,
+    clippy::manual_map,
+    clippy::expect_fun_call,
+    clippy::inherent_to_string,
+    clippy::ptr_arg,
+    clippy::into_iter_on_ref,
+    clippy::unnecessary_cast,
+    clippy::redundant_pattern_matching,
+    clippy::result_unit_err,
+)]
+
+pub mod engine;
+
+pub use engine::runtime::{Engine, EngineError};
@@ -1,33 +1,90 @@
-use std::{env, fs::File};
+use std::{env, fs, io::Read, process};
 
 use nebulang::engine::{
+    bytecode::{Program, MAGIC},
     compiler::Compiler,
-    config::{extract_config, Config},
-    parser::*,
+    config::{extract_config, Commands, Config},
+    loader::Loader,
+    vm::Vm,
 };
 
+const USAGE: &str = "Usage:
+    nebulang run <file> [flags] [-- args...]
+    nebulang build <file> [--out <file>] [flags]
+    nebulang check <file> [flags]
+
+Flags:
+    --qq <delimiter>    Global string delimiter
+    --low-mem           Read the source file without loading it in memory
+    --debug             Print the trace of the parser";
+
 fn main() {
     let args: Vec<String> = env::args().collect();
-    println!("{:?}", args);
 
-    let config: Config;
-    match extract_config(&args) {
-        Ok(imported_config) => config = imported_config,
-        Err(error) => panic!("{}", error),
+    let config: Config = match extract_config(&args) {
+        Ok(imported_config) => imported_config,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if config.debbuging {
+        println!("{:?}", config);
     }
 
-    println!("{:?}", config);
+    let program = match load(&config) {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    };
 
-    let _current_dir_str = env::current_dir().unwrap().to_str().unwrap().to_owned();
+    if config.command == Commands::Run {
+        let mut vm = Vm::new(program);
+        match vm.run(config.args.clone()) {
+            Ok(code) => process::exit(code),
+            Err(error) => {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        }
+    }
+}
 
-    let file = File::open(config.src.clone());
-    if let Ok(mut file) = file {
-        let mut parser = Parser::new(config.clone());
-        parser
-            .parse(file)
-            .expect(&format!("Could not finish parsing {}", config.src.clone()));
-        Compiler::compile(&parser, &config);
-    } else {
-        panic!("Source file {} not found!", config.src);
+/// Parses and compiles the source file and the files it imports, writing the
+/// ByteCode when building.
+/// Files that are already ByteCode are loaded as they are.
+fn load(config: &Config) -> Result<Program, String> {
+    // Only the magic is read so --low-mem keeps the source out of memory
+    let mut magic = [0; MAGIC.len()];
+    let is_bytecode = fs::File::open(&config.src)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|_| magic == MAGIC);
+    if is_bytecode {
+        let bytes = fs::read(&config.src).map_err(|error| {
+            format!("Could not load {}: {}", config.src, error)
+        })?;
+        return Program::from_bytes(&bytes).map_err(|error| {
+            format!("Could not load {}: {}", config.src, error)
+        });
     }
+    let modules = Loader::load(config).map_err(|error| {
+        if error.diagnostics.is_empty() {
+            return format!("Source file {} not found!", error.path);
+        }
+        let source = fs::read_to_string(&error.path).unwrap_or_default();
+        for diagnostic in &error.diagnostics {
+            eprintln!("{}", diagnostic.render(&source));
+        }
+        let count = error.diagnostics.len();
+        let errors = if count == 1 { "error" } else { "errors" };
+        format!("could not compile `{}` due to {} previous {}", error.path, count, errors)
+    })?;
+    let compiled = match config.command {
+        Commands::Build => Compiler::compile(&modules, config),
+        Commands::Run | Commands::Check => Compiler::emit_modules(&modules),
+    };
+    compiled.map_err(|error| format!("Could not compile {}: {}", config.src, error))
 }
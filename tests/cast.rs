@@ -0,0 +1,40 @@
+mod common;
+
+use common::{errors, run};
+use nebulang::Engine;
+
+#[test]
+fn values_are_cast_between_types() {
+    let (_, output) = run("casts", r#"#main (vec<str> args) int {
+    print("{} {} {} {}", 7~flt / 2.0, 3.9~int, "42"~int + 1, 65~chr);
+    print("{} {} {} {}", `A`~int, true~int, 1~bol, "2.5"~flt);
+    print("{}{}", 12~str, 1.5~str);
+    vec<str> none = []~vec<str>;
+    << none.length
+}
+"#);
+    assert_eq!(output, "3.5 3 43 A\n65 1 true 2.5\n121.5\n");
+}
+
+#[test]
+fn only_some_types_can_be_cast() {
+    let found = errors("bad_casts", r#"#main (vec<str> args) int {
+    chr a = 1.5~chr;
+    bol b = `a`~bol;
+    vec<int> c = 1~vec<int>;
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Cannot cast a value of type `flt` to `chr`",
+        "Cannot cast a value of type `chr` to `bol`",
+        "Mismatched types, expected `vec<int>`, found `int`",
+    ]);
+}
+
+#[test]
+fn casts_that_cannot_convert_stop_the_program() {
+    let mut engine = Engine::new();
+    engine.load_source("convert.nl", "#main (vec<str> args) int << \"abc\"~int;\n").unwrap();
+    assert_eq!(engine.run(vec![]).unwrap_err().to_string(), "Cannot cast \"abc\" to int in #main at 1:35");
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::{errors, run};
+
+#[test]
+fn ints_are_turned_into_flts() {
+    let (_, output) = run("widened", r#"#half (int a) flt << a / 2;
+#scale (flt a, flt b) flt << a * b;
+#main (vec<str> args) int {
+    flt a = 7;
+    flt b = 2;
+    print("{}", a / b);
+    flt c = 7 / 2;
+    print("{}", c);
+    c = 1 / 4;
+    print("{} {} {}", c, half(3), scale(2, 3) / 4.0);
+    << 0
+}
+"#);
+    assert_eq!(output, "3.5\n3.5\n0.25 1.5 1.5\n");
+}
+
+#[test]
+fn every_path_returns() {
+    let found = errors("paths", r#"#positive (int a) int {
+    ? a > 0 {
+        << 1
+    }
+}
+#forever (int a) int {
+    ! {
+        ? a > 0 {
+            << 1
+        }
+    }
+}
+#until (int a) int {
+    ! {
+        ? a > 0 {
+            << !
+        }
+    }
+}
+#nothing (int a) {
+    ? a > 0 {
+        << 
+    }
+}
+#main (vec<str> args) int {
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Not all paths of `positive` return a value of type `int`",
+        "Not all paths of `until` return a value of type `int`",
+    ]);
+}
+
+#[test]
+fn bools_are_written_true_and_false() {
+    let (code, output) = run("bools", r#"#main (vec<str> args) int {
+    bol done = false;
+    ? true {
+        done = true == true;
+    }
+    print("{} {}", done, false);
+    << true~int
+}
+"#);
+    assert_eq!((code, output.as_str()), (1, "true false\n"));
+}
+
+#[test]
+fn mismatched_types_are_reported() {
+    let found = errors("mismatched", r#"#add (int a, int b) int << a + b;
+#shout (str text) {
+    print(text);
+}
+#main (vec<str> args) int {
+    int a = "one";
+    str b = add(1, 2);
+    int c = add(1);
+    int d = add(1, "2");
+    int e = nothing(1);
+    int f = shout("x");
+    bol g = "a" < 1;
+    << "done"
+}
+"#);
+    assert_eq!(found, vec![
+        "Mismatched types, expected `int`, found `str`",
+        "Mismatched types, expected `str`, found `int`",
+        "`add` expects 2 arguments, found 1",
+        "Mismatched types, expected `int`, found `str`",
+        "Cannot find function `nothing`",
+        "Mismatched types, expected `int`, found `void`",
+        "Cannot use `<` between `str` and `int`",
+        "Mismatched types, expected `int`, found `str`",
+    ]);
+}
+
+#[test]
+fn well_typed_programs_pass() {
+    let (code, output) = run("typed", r#"#average (vec<flt> values) flt {
+    flt total = 0;
+    int index = 0;
+    ! {
+        ? index >= values.length {
+            << !
+        }
+        total += values[index];
+        index++;
+    }
+    << total / values.length~flt
+}
+#main (vec<str> args) int {
+    str name = "mean";
+    chr first = name[0];
+    bol big = average([1.0, 2.0, 4.5]) > 2.0;
+    print("{} {} {}", first, big, average([1.5, 2.5]));
+    << 0
+}
+"#);
+    assert_eq!((code, output.as_str()), (0, "m true 2\n"));
+}
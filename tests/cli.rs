@@ -0,0 +1,78 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use common::files;
+use nebulang::engine::bytecode::MAGIC;
+
+fn nebulang(directory: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_nebulang"))
+        .current_dir(directory)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+const ECHO: &str = r#"#main (vec<str> args) int {
+    print("{}", args.join(" "));
+    << args.length
+}
+"#;
+
+#[test]
+fn run_passes_the_arguments_and_exits_with_the_code() {
+    let directory = files("cli_run", &[("echo.nl", ECHO)]);
+    let output = nebulang(&directory, &["run", "echo.nl", "--", "first", "second"]);
+    assert_eq!(stdout(&output), "first second\n");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn built_bytecode_runs_like_the_source() {
+    let directory = files("cli_build", &[("echo.nl", ECHO)]);
+    let output = nebulang(&directory, &["build", "echo.nl", "--out", "echo.nbc"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(fs::read(directory.join("echo.nbc")).unwrap().starts_with(&MAGIC));
+    let output = nebulang(&directory, &["run", "echo.nbc", "--", "again"]);
+    assert_eq!(stdout(&output), "again\n");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn check_reports_errors_without_running() {
+    let directory = files("cli_check", &[
+        ("good.nl", "#main (vec<str> args) int {\n    print(\"ran\");\n    << 3\n}\n"),
+        ("bad.nl", "#main (vec<str> args) int {\n    << missing\n}\n"),
+        ("worse.nl", "#main (vec<str> args) int {\n    print(missing);\n    << other\n}\n"),
+    ]);
+    let output = nebulang(&directory, &["check", "good.nl"]);
+    assert_eq!((output.status.code(), stdout(&output)), (Some(0), String::new()));
+    let output = nebulang(&directory, &["check", "bad.nl"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("error: Cannot find `missing` in this scope"));
+    assert!(stderr(&output).contains("--> bad.nl:2:8"));
+    assert!(stderr(&output).contains("error: could not compile `bad.nl` due to 1 previous error\n"));
+    let output = nebulang(&directory, &["check", "worse.nl"]);
+    assert!(stderr(&output).contains("error: could not compile `worse.nl` due to 2 previous errors\n"));
+}
+
+#[test]
+fn mistakes_in_the_command_are_reported() {
+    let directory = files("cli_usage", &[]);
+    let output = nebulang(&directory, &["launch", "main.nl"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("Usage:"));
+    let output = nebulang(&directory, &["run", "missing.nl"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).contains("missing.nl not found"));
+}
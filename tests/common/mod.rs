@@ -0,0 +1,48 @@
+//! Helpers shared by the tests, each test file only uses some of them
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use nebulang::engine::{
+    config::Config,
+    loader::Loader,
+};
+use nebulang::Engine;
+
+/// Loads the source from memory, returning the messages of its errors
+pub fn errors(name: &str, source: &str) -> Vec<String> {
+    let config = Config::new(&format!("{}.nl", name));
+    let result = Loader::new(&config).load_source(source);
+    match result {
+        Ok(_) => vec![],
+        Err(error) => error.diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect(),
+    }
+}
+
+/// Runs the #main function of the source, returning its exit code and what
+/// it printed
+pub fn run(name: &str, source: &str) -> (i32, String) {
+    let mut engine = Engine::new();
+    if let Err(error) = engine.load_source(&format!("{}.nl", name), source) {
+        panic!("{}", error);
+    }
+    let code = engine.run(vec![]).unwrap_or_else(|error| panic!("{}", error));
+    (code, engine.take_output())
+}
+
+/// A new directory holding the files, for what has to be read from disk
+pub fn files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("nebulang-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    for (path, source) in files {
+        let path = directory.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+    directory
+}
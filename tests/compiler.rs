@@ -0,0 +1,91 @@
+use nebulang::engine::{
+    bytecode::{ByteCodeError, Program, MAGIC, VERSION},
+    compiler::Compiler,
+    config::Config,
+    loader::Loader,
+    vm::{Value, Vm},
+};
+use nebulang::{Engine, EngineError};
+
+fn compile(name: &str, source: &str) -> Program {
+    let modules = Loader::new(&Config::new(name)).load_source(source).unwrap();
+    Compiler::emit_modules(&modules).unwrap()
+}
+
+fn compile_error(source: &str) -> String {
+    match Engine::new().load_source("wide.nl", source) {
+        Err(EngineError::Compile(error)) => error.to_string(),
+        Err(error) => panic!("{}", error),
+        Ok(()) => panic!("wide.nl compiled"),
+    }
+}
+
+#[test]
+fn calls_take_at_most_255_arguments() {
+    let placeholders = "{}".repeat(255);
+    let values: Vec<String> = (0..255).map(|value| value.to_string()).collect();
+    let call = format!("#main () {{ print(\"{}\", {}); }}\n", placeholders, values.join(", "));
+    assert_eq!(compile_error(&call), "#print cannot take more than 255 arguments");
+
+    let params: Vec<String> = (0..256).map(|param| format!("int p{}", param)).collect();
+    let declaration = format!("#wide ({}) int << 0;\n", params.join(", "));
+    assert_eq!(compile_error(&declaration), "#wide cannot take more than 255 arguments");
+}
+
+#[test]
+fn programs_survive_the_bytecode_round_trip() {
+    let program = compile("round.nl", r#"^map@std;
+^point {
+    int x;
+    int y;
+}
+^counter {
+    int start;
+}
+#get^counter (self self, int index) int << self.start + index;
+^listed {
+    int start;
+}
+^counter^listed;
+#get^listed (self self, int index) int << self.start - index;
+#score (int n) flt {
+    point p = { x: n, y: 2 };
+    vec<chr> letters = [`a`, `b`];
+    map<str><int> ages = ["a", 1];
+    counter c = listed { start: 10 };
+    flt total = 0.5;
+    ! {
+        ? total > 3.0 {
+            << !
+        }
+        total += 1.0;
+    }
+    str text = format("{}{}", letters[1], ages.a);
+    << total + (p.x * p.y + c[1] + text.length + "2"~int + true~int)~flt;
+}
+"#);
+    let bytes = program.to_bytes();
+    assert_eq!(&bytes[..4], &MAGIC);
+    let decoded = Program::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert_eq!(decoded.strings, program.strings);
+    assert_eq!(decoded.kinds, program.kinds);
+    for (decoded, function) in decoded.functions.iter().zip(program.functions.iter()) {
+        assert_eq!(decoded.code, function.code);
+    }
+    let mut vm = Vm::new(decoded);
+    let score = vm.function_index("score").unwrap();
+    assert_eq!(vm.call(score, vec![Value::Int(3)]).unwrap(), Value::Flt(3.5 + 6.0 + 9.0 + 2.0 + 2.0 + 1.0));
+}
+
+#[test]
+fn broken_bytecode_is_rejected() {
+    let bytes = compile("broken.nl", "#main () int << 1;\n").to_bytes();
+    assert_eq!(Program::from_bytes(b"NOPE\0\0").unwrap_err(), ByteCodeError::Magic);
+    let mut old = bytes.clone();
+    old[4..6].copy_from_slice(&(VERSION - 1).to_le_bytes());
+    assert_eq!(Program::from_bytes(&old).unwrap_err(), ByteCodeError::Version(VERSION - 1));
+    for end in 0..bytes.len() {
+        assert!(Program::from_bytes(&bytes[..end]).is_err());
+    }
+}
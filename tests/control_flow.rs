@@ -0,0 +1,109 @@
+mod common;
+
+use common::{errors, run};
+
+#[test]
+fn conditionals_run_their_body_when_true() {
+    let (_, output) = run("conditionals", r#"#sign (int n) str {
+    ? n > 0 {
+        << "positive"
+    }
+    ? n < 0 {
+        ? n < -9 {
+            << "very negative"
+        }
+        << "negative"
+    }
+    << "zero"
+}
+#main (vec<str> args) int {
+    print("{} {} {} {}", sign(3), sign(-2), sign(-10), sign(0));
+    ? 1 == 2 {
+        print("skipped");
+    }
+    ? 1 < 2 { print("single line"); }
+    << 0
+}
+"#);
+    assert_eq!(output, "positive negative very negative zero\nsingle line\n");
+}
+
+#[test]
+fn conditions_are_bols() {
+    let found = errors("condition", r#"#main (vec<str> args) int {
+    ? 1 {
+        print("one");
+    }
+    << 0
+}
+"#);
+    assert_eq!(found, vec!["Mismatched types, expected `bol`, found `int`"]);
+}
+
+#[test]
+fn conditionals_need_a_body() {
+    let found = errors("bodiless", r#"#main (vec<str> args) int {
+    ? 1 < 2
+    << 0
+}
+"#);
+    assert_eq!(found, vec!["Unexpected `}`", "Unexpected end of file while parsing Condition"]);
+}
+
+#[test]
+fn loops_run_until_they_break() {
+    let (_, output) = run("loops", r#"#main (vec<str> args) int {
+    int row = 0;
+    ! {
+        ? row >= 3 {
+            << !
+        }
+        str line = "";
+        int column = 0;
+        ! {
+            ? column > row {
+                << !
+            }
+            line = format("{}{}", line, column);
+            column++;
+        }
+        print(line);
+        row++;
+    }
+    print("after {}", row);
+    << 0
+}
+"#);
+    assert_eq!(output, "0\n01\n012\nafter 3\n");
+}
+
+#[test]
+fn returns_leave_loops() {
+    let (code, _) = run("loop_return", r#"#find (int target) int {
+    int at = 0;
+    ! {
+        ? at * at >= target {
+            << at
+        }
+        at++;
+    }
+}
+#main (vec<str> args) int << find(50);
+"#);
+    assert_eq!(code, 8);
+}
+
+#[test]
+fn breaks_are_only_in_loops() {
+    let found = errors("stray_break", r#"#main (vec<str> args) int {
+    ? 1 < 2 {
+        << !
+    }
+    ! {
+        << !
+    }
+    << 0
+}
+"#);
+    assert_eq!(found, vec!["Cannot break outside of a loop"]);
+}
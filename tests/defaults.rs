@@ -0,0 +1,28 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn chrs_without_a_value_are_nul() {
+    let (_, output) = run("chr_default", r#"#main (vec<str> args) int {
+    chr c;
+    print("{}", c~int);
+    << 0
+}
+"#);
+    assert_eq!(output, "0\n");
+}
+
+#[test]
+fn vecs_and_maps_without_a_value_are_empty() {
+    let (_, output) = run("collection_default", r#"^map@std;
+#main (vec<str> args) int {
+    vec<int> v;
+    map<str><int> m;
+    v = v.push(1);
+    print("{} {} {}", v.length, m.keys().length, m.has("a"));
+    << 0
+}
+"#);
+    assert_eq!(output, "1 0 false\n");
+}
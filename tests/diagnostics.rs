@@ -0,0 +1,68 @@
+use nebulang::engine::{
+    config::Config,
+    diagnostic::Diagnostic,
+    loader::Loader,
+};
+
+fn diagnostics(source: &str) -> Vec<Diagnostic> {
+    let config = Config::new("spans.nl");
+    match Loader::new(&config).load_source(source) {
+        Ok(_) => vec![],
+        Err(error) => error.diagnostics,
+    }
+}
+
+/// Start, end and message of each diagnostic
+fn spans(source: &str) -> Vec<String> {
+    diagnostics(source)
+        .into_iter()
+        .map(|diagnostic| format!(
+            "{}:{}-{}:{} {}",
+            diagnostic.start.line, diagnostic.start.column,
+            diagnostic.end.line, diagnostic.end.column,
+            diagnostic.message,
+        ))
+        .collect()
+}
+
+#[test]
+fn diagnostics_span_what_they_are_about() {
+    let found = spans(r#"#main (vec<str> args) int {
+    int count = missing;
+    bol small = 1 / "x" <= 2 - "a";
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "2:17-2:23 Cannot find `missing` in this scope",
+        "3:19-3:19 Cannot use `/` between `int` and `str`",
+        "3:30-3:30 Cannot use `-` between `int` and `str`",
+    ]);
+}
+
+#[test]
+fn diagnostics_render_the_line_they_are_on() {
+    let source = "#main (vec<str> args) int {\n    str name = \"nebula;\n    << 0\n}\n";
+    let found = diagnostics(source);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].render(source), "error: Unterminated string
+ --> spans.nl:2:16
+  |
+2 |     str name = \"nebula;
+  |                ^
+  = note: expected the string to be closed by \"
+");
+}
+
+#[test]
+fn every_error_of_the_file_is_reported() {
+    let found = spans(r#"#first () int << "one";
+#second () str << 2;
+#first () int << 1;
+"#);
+    assert_eq!(found, vec![
+        "3:2-3:6 Function `first` is already defined",
+        "1:18-1:22 Mismatched types, expected `int`, found `str`",
+        "2:19-2:19 Mismatched types, expected `str`, found `int`",
+    ]);
+}
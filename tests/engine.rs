@@ -0,0 +1,154 @@
+use std::rc::Rc;
+
+use nebulang::engine::{
+    core::Native,
+    types::{DataTypes, TypeSignature},
+    vm::Value,
+};
+use nebulang::{Engine, EngineError};
+
+fn int() -> TypeSignature {
+    TypeSignature::new(DataTypes::Int)
+}
+
+fn vec2() -> TypeSignature {
+    TypeSignature::new(DataTypes::User("vec2".to_string()))
+}
+
+#[test]
+fn functions_are_called_with_rust_values() {
+    let mut engine = Engine::new();
+    engine.load_source("math.nl", r#"#add (int a, int b) int << a + b;
+#greet (str name) str << format("Hello {}!", name);
+"#).unwrap();
+    assert_eq!(engine.call("add", vec![Value::Int(2), Value::Int(3)]).unwrap(), Value::Int(5));
+    assert_eq!(
+        engine.call("greet", vec![Value::Str("World".into())]).unwrap(),
+        Value::Str("Hello World!".into())
+    );
+}
+
+#[test]
+fn printing_does_not_reach_stdout() {
+    let mut engine = Engine::new();
+    engine.load_source("main.nl", r#"#main (vec<str> args) int {
+    print("{} arguments", args.length);
+    print("done");
+    << 3
+}
+"#).unwrap();
+    assert_eq!(engine.run(vec!["a".to_string()]).unwrap(), 3);
+    assert_eq!(engine.take_output(), "1 arguments\ndone\n");
+    assert_eq!(engine.take_output(), "");
+}
+
+#[test]
+fn host_functions_and_structs() {
+    let mut engine = Engine::new();
+    engine.register_struct("vec2", vec![("x", int()), ("y", int())]).unwrap();
+    engine.register_function("length2", Native::new(vec![vec2()], int(), |args| match args {
+        [Value::Struct(fields, _)] => match fields.as_slice() {
+            [Value::Int(x), Value::Int(y)] => Ok(Value::Int(x * x + y * y)),
+            _ => Err("#length2 takes a vec2".to_string()),
+        },
+        _ => Err("#length2 takes a vec2".to_string()),
+    })).unwrap();
+    engine.load_source("game.nl", r#"#step (vec2 position) vec2 {
+    vec2 next = { x: position.x + 1, y: position.y * 2 };
+    << next
+}
+#distance (int x, int y) int {
+    vec2 point = { x: x, y: y };
+    << length2(point)
+}
+"#).unwrap();
+    let position = Value::Struct(Rc::new(vec![Value::Int(1), Value::Int(2)]), None);
+    assert_eq!(
+        engine.call("step", vec![position]).unwrap(),
+        Value::Struct(Rc::new(vec![Value::Int(2), Value::Int(4)]), None)
+    );
+    assert_eq!(engine.call("distance", vec![Value::Int(3), Value::Int(4)]).unwrap(), Value::Int(25));
+}
+
+#[test]
+fn structs_are_checked_field_by_field() {
+    let mut engine = Engine::new();
+    engine.register_struct("vec2", vec![("x", int()), ("y", int())]).unwrap();
+    engine.load_source("score.nl", "#score (vec2 position) int << position.x + position.y;").unwrap();
+    let short = Value::Struct(Rc::new(vec![Value::Int(1)]), None);
+    let wrong = Value::Struct(Rc::new(vec![Value::Str("a".into()), Value::Int(2)]), None);
+    for position in [short, wrong] {
+        let error = engine.call("score", vec![position]).unwrap_err();
+        assert!(matches!(error, EngineError::Argument { position: 1, .. }));
+        assert_eq!(error.to_string(), "Argument 1 of `score` has to be of type `vec2`");
+    }
+    let position = Value::Struct(Rc::new(vec![Value::Int(1), Value::Int(2)]), None);
+    assert_eq!(engine.call("score", vec![position]).unwrap(), Value::Int(3));
+}
+
+#[test]
+fn natives_return_the_type_they_are_registered_with() {
+    let mut engine = Engine::new();
+    engine.register_struct("vec2", vec![("x", int()), ("y", int())]).unwrap();
+    engine.register_function("lie", Native::new(vec![], int(), |_| Ok(Value::Str("1".into())))).unwrap();
+    engine.register_function("origin", Native::new(vec![], vec2(), |_| {
+        Ok(Value::Struct(Rc::new(vec![Value::Int(0)]), None))
+    })).unwrap();
+    engine.load_source("lies.nl", r#"#score () int << lie() + 1;
+#start () int << origin().x;
+"#).unwrap();
+    assert_eq!(
+        engine.call("score", vec![]).unwrap_err().to_string(),
+        "Native function `lie` returned a str instead of a value of type `int` in #score at 1:18"
+    );
+    assert_eq!(
+        engine.call("start", vec![]).unwrap_err().to_string(),
+        "Native function `origin` returned a struct instead of a value of type `vec2` in #start at 2:18"
+    );
+}
+
+#[test]
+fn names_are_registered_once() {
+    let mut engine = Engine::new();
+    let native = || Native::new(vec![], int(), |_| Ok(Value::Int(1)));
+    assert!(matches!(engine.register_function("print", native()), Err(EngineError::Taken(_))));
+    engine.register_struct("vec2", vec![("x", int())]).unwrap();
+    assert!(matches!(engine.register_struct("vec2", vec![]), Err(EngineError::Taken(_))));
+}
+
+#[test]
+fn errors_are_returned() {
+    let mut engine = Engine::new();
+    let error = engine.load_source("bad.nl", r#"#broken () int {
+    << missing
+}
+"#).unwrap_err();
+    assert_eq!(error.to_string(), "bad.nl:2:8: Cannot find `missing` in this scope");
+
+    engine.load_source("div.nl", r#"#divide (int a, int b) int << a / b;
+"#).unwrap();
+    assert!(matches!(engine.call("nothing", vec![]), Err(EngineError::UnknownFunction(_))));
+    assert!(matches!(
+        engine.call("divide", vec![Value::Int(1), Value::Int(0)]),
+        Err(EngineError::Runtime(_))
+    ));
+    // The failed call does not get in the way of the next one
+    assert_eq!(engine.call("divide", vec![Value::Int(6), Value::Int(3)]).unwrap(), Value::Int(2));
+}
+
+#[test]
+fn arguments_have_the_types_of_the_parameters() {
+    let mut engine = Engine::new();
+    engine.load_source("types.nl", r#"#divide (int a, int b) int << a / b;
+#first<t> (vec<t> items) t << items[0];
+#total (vec<int> items) int << items.length;
+"#).unwrap();
+    let error = engine.call("divide", vec![Value::Int(1), Value::Str("2".into())]).unwrap_err();
+    assert_eq!(error.to_string(), "Argument 2 of `divide` has to be of type `int`");
+    let strings = Value::Vec(Rc::new(vec![Value::Str("a".into())]));
+    assert_eq!(engine.call("first", vec![strings.clone()]).unwrap(), Value::Str("a".into()));
+    assert!(matches!(
+        engine.call("total", vec![strings]),
+        Err(EngineError::Argument { position: 1, .. })
+    ));
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{errors, run};
+
+#[test]
+fn comments_end_the_line_they_are_on() {
+    let (code, output) = run("comments", r#"// This is a comment
+
+// This is a function declaration
+#add (int a, int b) int {
+    << a + b; // Return is typed <<
+}
+
+// Functions can also be declared in a short syntax for 1-liners
+#subtract (int a, int b) int << a - b;
+
+// Programs always start from the #main function. This function will have the
+// arguments passed from the call from the terminal
+#main (vec<str> args) int {
+    int a = 1;
+    int b = 2 // Semi-colon is optional
+    int c = add(a, b);
+    print("{}", c); // Rust-like  🦀
+    print(subtract(c, 1) //inline; ~str);
+    << 0;
+}
+"#);
+    assert_eq!((code, output.as_str()), (0, "3\n2\n"));
+}
+
+#[test]
+fn operators_bind_by_precedence() {
+    let (_, output) = run("precedence", r#"#main (vec<str> args) int {
+    print("{} {} {}", 1 + 2 * 3, (1 + 2) * 3, 10 - 4 - 3);
+    print("{} {} {}", 20 / 2 / 5, 2 * 7 % 4, -2 * -3);
+    print("{} {} {}", 1 << 2 + 1, 6 & 3 | 8, 1 | 2 & 3);
+    print("{} {}", 1 + 1 == 2, (2 - 3) * (4 + 1) < -4);
+    print("{}", ((((7)))));
+    << 0
+}
+"#);
+    assert_eq!(output, "7 9 3\n2 2 6\n8 10 3\ntrue true\n7\n");
+}
+
+#[test]
+fn comparisons_are_not_chained() {
+    let found = errors("chained", r#"#main (vec<str> args) int {
+    bol sorted = 1 < 2 < 3;
+    int unclosed = (1 + 2;
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Comparison operators cannot be chained",
+        "Unexpected `}`",
+        "Unexpected end of file while parsing Group",
+    ]);
+}
+
+#[test]
+fn missing_operators_show_what_was_written() {
+    let found = errors("missing_operator", r#"#main (vec<str> args) int {
+    flt version = 1.5.3;
+    str spaced = qq "space";
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Unexpected `.`",
+        "Expected an operator, found `3`",
+        "Expected an operator, found `\"space\"`",
+    ]);
+}
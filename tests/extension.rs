@@ -0,0 +1,145 @@
+mod common;
+
+use common::{errors, run};
+
+#[test]
+fn structs_extend_vec() {
+    let (_, output) = run("conveyor", r#"^conveyor<t> {
+    int max;
+    vec<t> items;
+}
+#new^conveyor<t> (int max, vec<t> items) self {
+    << conveyor<t> {
+        max: max,
+        items: items,
+    }
+}
+^vec<t>^conveyor<t>;
+#push^conveyor<t> (self self, t new_item) self {
+    vec<t> new_items = self.items;
+    ? self.length >= self.max {
+        new_items = new_items.unshift();
+    }
+    new_items = new_items.push(new_item);
+    << conveyor<t> { max: self.max, items: new_items }
+}
+#get^conveyor<t> (self self, int index) t {
+    << self.items[index]
+}
+#count (vec<str> list) int << list.length;
+#main (vec<str> args) int {
+    conveyor<str> todo = conveyor:new(2, [
+        "plant tree",
+        "pet crab"
+    ]~vec<str>);
+    todo = todo.push("take vitamins");
+    print("{},{}", todo[0], todo[1]);
+    print("{} {} {}", todo.length, todo.join("|"), count(todo));
+    conveyor<int> kept = conveyor:new(5, [1]);
+    kept = kept.unshift();
+    print("{} {}", kept.max, kept.length);
+    << 0
+}
+"#);
+    assert_eq!(output, "pet crab,take vitamins\n2 pet crab|take vitamins 2\n5 0\n");
+}
+
+#[test]
+fn replaced_methods_are_called_through_the_base() {
+    let (_, output) = run("dispatch", r#"^counter {
+    int start;
+}
+#get^counter (self self, int index) int << self.start + index;
+#name^counter (self self) str << "counter";
+^listed {
+    int start;
+    int step;
+}
+^counter^listed;
+#get^listed (self self, int index) int << self.start + index * self.step;
+#show (counter c) int << c[2];
+#main (vec<str> args) int {
+    listed l = listed { start: 1, step: 10 };
+    counter c = l;
+    print("{} {} {} {}", c[2], show(l), c.name(), l[2]);
+    counter d = counter { start: 1 };
+    print("{}", d[2]);
+    << 0
+}
+"#);
+    assert_eq!(output, "21 21 counter 21\n3\n");
+}
+
+#[test]
+fn replaced_methods_keep_their_signature() {
+    let found = errors("replaced", r#"^counter {
+    int start;
+}
+#get^counter (self self, int index) int << self.start + index;
+#name^counter (self self) str << "counter";
+^listed {
+    int start;
+}
+^counter^listed;
+#get^listed (self self, str index) int << self.start;
+#name^listed (self self) int << 1;
+^stack {
+    vec<int> items;
+}
+^vec<int>^stack;
+#push^stack (self self, str item) self << self;
+^empty {
+    int a;
+}
+^vec<int>^empty;
+^str^text;
+#main (vec<str> args) int << 0;
+"#);
+    assert_eq!(found, vec![
+        "`empty` needs a field of type `vec<int>` to extend `vec`",
+        "`str` cannot be extended, only `vec` and structs declared in the program can",
+        "`listed:get` has to take the same parameters and return the same type as the `counter:get` it replaces",
+        "`listed:name` has to take the same parameters and return the same type as the `counter:name` it replaces",
+        "`stack:push` has to take the same parameters and return the same type as the `vec:push` it replaces",
+    ]);
+}
+
+#[test]
+fn extending_structs_inherit_their_functions() {
+    let (_, output) = run("inherit", r#"^counter {
+    int start;
+}
+#new^counter (int start) self << { start: start };
+#get^counter (self self, int index) int << self.start + index;
+#doubled^counter (self self) int << self.start * 2;
+^limited {
+    int start;
+    int max;
+}
+^counter^limited;
+#main (vec<str> args) int {
+    limited l = { start: 3, max: 4 };
+    counter c = counter:new(5);
+    print("{} {} {} {}", l.doubled(), l[1], c[1], l.max);
+    << 0
+}
+"#);
+    assert_eq!(output, "6 4 6 4\n");
+}
+
+#[test]
+fn extending_structs_keep_the_fields_of_their_base() {
+    let found = errors("extend_fields", r#"^counter {
+    int start;
+}
+^limited {
+    int max;
+}
+^counter^limited;
+^nothing^other;
+"#);
+    assert_eq!(found, vec![
+        "`limited` has to start with the fields of `counter` to extend it",
+        "Cannot find struct `other` to extend `nothing` with",
+    ]);
+}
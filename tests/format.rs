@@ -0,0 +1,79 @@
+mod common;
+
+use common::{errors, run};
+use nebulang::engine::{
+    core::format,
+    vm::Value,
+};
+
+#[test]
+fn placeholders_take_the_values_in_order() {
+    let values = [Value::Int(1), Value::Flt(1.5), Value::Str("a".into())];
+    assert_eq!(format("{}:{} {}!", &values).unwrap(), "1:1.5 a!");
+}
+
+#[test]
+fn braces_are_escaped_by_doubling() {
+    assert_eq!(format("{{}} {{{}}}", &[Value::Chr('x')]).unwrap(), "{} {x}");
+}
+
+#[test]
+fn values_have_to_match_the_placeholders() {
+    assert_eq!(
+        format("{} {}", &[Value::Int(1)]).unwrap_err(),
+        "Expected 2 values for the placeholders of the format, found 1"
+    );
+    assert!(format("{x}", &[Value::Int(1)]).is_err());
+    assert!(format("}", &[]).is_err());
+}
+
+#[test]
+fn literal_formats_are_checked() {
+    let found = errors("literal", r#"#main (vec<str> args) int {
+    print("{}:{}", 1, 2);
+    str line = format("{} {{}}", 1);
+    print(line);
+    print("{} {}", 1);
+    print("{", 1);
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Expected 2 values for the placeholders of the format, found 1",
+        "Expected `}` right after `{` in the format",
+    ]);
+}
+
+#[test]
+fn formats_are_strings() {
+    let found = errors("strings", r#"#main (vec<str> args) int {
+    int count = 2;
+    print(count, 1);
+    int line = format("{}", count);
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Mismatched types, expected `str`, found `int`",
+        "Mismatched types, expected `int`, found `str`",
+    ]);
+}
+
+#[test]
+fn a_format_alone_unescapes_its_braces() {
+    let (_, output) = run("alone", r#"#main (vec<str> args) int {
+    print("a {{b}} c");
+    print("a {{b}} {}", 1);
+    print("{}", format("{{}}"));
+    print(2);
+    << 0
+}
+"#);
+    assert_eq!(output, "a {b} c\na {b} 1\n{}\n2\n");
+    let found = errors("alone_bad", r#"#main (vec<str> args) int {
+    print("a {} c");
+    << 0
+}
+"#);
+    assert_eq!(found, vec!["Expected 1 values for the placeholders of the format, found 0"]);
+}
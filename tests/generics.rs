@@ -0,0 +1,95 @@
+mod common;
+
+use common::{errors, run};
+
+#[test]
+fn struct_literals_name_their_struct() {
+    let (_, output) = run("named", r#"^pair<t> {
+    t left;
+    vec<t> right;
+}
+^point {
+    int x;
+    int y;
+}
+#new^pair<t> (t left, vec<t> right) self {
+    << pair<t> {
+        left: left,
+        right: right,
+    }
+}
+#main (vec<str> args) int {
+    pair<str> p = pair:new("a", ["b"]);
+    pair<vec<int>> q = pair<vec<int>> { left: [1], right: [[2, 3]] };
+    bol smaller = 1 < 2;
+    print("{} {} {} {}", p.left, q.right[0][1], point { x: 4, y: 0 }.x, smaller);
+    << 0
+}
+"#);
+    assert_eq!(output, "a 3 4 true\n");
+}
+
+#[test]
+fn named_struct_literals_are_checked() {
+    let found = errors("named_bad", r#"^pair<t> {
+    t left;
+}
+#main (vec<str> args) int {
+    pair<int> a = pair<str> { left: "a" };
+    pair<int> b = pair<int> { left: "a" };
+    pair<int> c = nope<int> { left: 1 };
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Mismatched types, expected `pair<int>`, found `pair<str>`",
+        "Mismatched types, expected `int`, found `str`",
+        "Cannot find type `nope`",
+    ]);
+}
+
+#[test]
+fn type_parameters_are_filled_by_their_uses() {
+    let (_, output) = run("generic_functions", r#"^box<t> {
+    t inner;
+}
+#wrap<t> (t value) box<t> << box<t> { inner: value };
+#first<t> (vec<t> items) t << items[0];
+#get^box<t> (self self) t << self.inner;
+#main (vec<str> args) int {
+    box<int> a = wrap(1);
+    box<str> b = wrap("x");
+    box<vec<int>> c = wrap([4, 5]);
+    print("{} {} {} {}", a.get() + 1, b.get(), first(["p", "q"]), first(c.get()));
+    << 0
+}
+"#);
+    assert_eq!(output, "2 x p 4\n");
+}
+
+#[test]
+fn type_parameters_are_checked() {
+    let found = errors("generic_errors", r#"^box<t> {
+    t inner;
+}
+#wrap<t> (t value) box<t> << box<t> { inner: value };
+#first<t> (vec<t> items) t << items[0];
+#get^box<t> (self self) t << self.inner;
+#main (vec<str> args) int {
+    str c = first([1, 2]);
+    box<int> d = { inner: "no" };
+    box e = { inner: 1 };
+    box<int><str> f = { inner: 1 };
+    box<str> b = wrap("x");
+    int g = b.get();
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Mismatched types, expected `str`, found `int`",
+        "Mismatched types, expected `int`, found `str`",
+        "`box` expects 1 type parameters, found 0",
+        "`box` expects 1 type parameters, found 2",
+        "Mismatched types, expected `int`, found `str`",
+    ]);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::files;
+use nebulang::engine::{config::Config, loader::Loader};
+use nebulang::engine::vm::Value;
+use nebulang::{Engine, EngineError};
+
+/// Runs the main.nl of the directory, returning what it printed
+fn run_main(name: &str, sources: &[(&str, &str)]) -> String {
+    let directory = files(name, sources);
+    let mut engine = Engine::new();
+    engine.load_file(&directory.join("main.nl").to_string_lossy()).unwrap_or_else(|error| panic!("{}", error));
+    engine.run(vec![]).unwrap_or_else(|error| panic!("{}", error));
+    engine.take_output()
+}
+
+/// Messages of the errors of loading the main.nl of the directory
+fn import_errors(name: &str, sources: &[(&str, &str)]) -> Vec<String> {
+    let directory = files(name, sources);
+    let config = Config::new(&directory.join("main.nl").to_string_lossy());
+    match Loader::load(&config) {
+        Ok(_) => vec![],
+        Err(error) => error.diagnostics.into_iter().map(|diagnostic| diagnostic.message).collect(),
+    }
+}
+
+#[test]
+fn functions_and_structs_are_imported() {
+    let output = run_main("imports", &[
+        ("main.nl", r#"#multiply@lib/math.nl
+#twice@lib/math.nl
+^point@shapes.nl
+#name () str << "main";
+#main (vec<str> args) int {
+    point p = point:new(2, 3);
+    print("{} {} {} {}", multiply(2, 3), twice(4), p.area(), name());
+    << 0
+}
+"#),
+        ("lib/math.nl", r#"#name () str << "math";
+<< #multiply (int a, int b) int << a * b;
+<< #twice (int a) int << multiply(a, 2);
+"#),
+        ("shapes.nl", r#"#multiply@lib/math.nl
+<< ^point {
+    int x;
+    int y;
+}
+#new^point (int x, int y) self << { x: x, y: y };
+#area^point (self self) int << multiply(self.x, self.y);
+"#),
+    ]);
+    assert_eq!(output, "6 8 6 main\n");
+}
+
+#[test]
+fn imported_functions_check_their_arguments() {
+    let directory = files("imported_arguments", &[
+        ("main.nl", "#twice@lib/math.nl\n#main (vec<str> args) int << twice(2);\n"),
+        ("lib/math.nl", "<< #twice (int a) int << a * 2;\n"),
+    ]);
+    let mut engine = Engine::new();
+    engine.load_file(&directory.join("main.nl").to_string_lossy()).unwrap_or_else(|error| panic!("{}", error));
+    assert_eq!(engine.call("twice@lib/math.nl", vec![Value::Int(4)]).unwrap(), Value::Int(8));
+    let error = engine.call("twice@lib/math.nl", vec![Value::Str("4".into())]).unwrap_err();
+    assert!(matches!(error, EngineError::Argument { position: 1, .. }));
+    assert_eq!(error.to_string(), "Argument 1 of `twice@lib/math.nl` has to be of type `int`");
+}
+
+#[test]
+fn only_exports_can_be_imported() {
+    let found = import_errors("exports", &[
+        ("main.nl", r#"#hidden@dep.nl
+#missing@dep.nl
+#anything@nowhere.nl
+#main (vec<str> args) int << 0;
+"#),
+        ("dep.nl", r#"#hidden () int << 1;
+"#),
+    ]);
+    assert_eq!(found, vec![
+        "`dep.nl` does not export `hidden`",
+        "`dep.nl` does not export `missing`",
+        "Cannot find the file `nowhere.nl`",
+    ]);
+}
+
+#[test]
+fn files_cannot_import_each_other() {
+    let found = import_errors("cycles", &[
+        ("main.nl", r#"#one@a.nl
+#main (vec<str> args) int << one();
+"#),
+        ("a.nl", r#"#two@b.nl
+<< #one () int << two();
+"#),
+        ("b.nl", r#"#one@a.nl
+<< #two () int << 2;
+"#),
+    ]);
+    assert_eq!(found, vec!["`a.nl` cannot be imported, it imports this file"]);
+}
+
+#[test]
+fn errors_of_imported_files_stop_the_loading() {
+    let directory = files("imported_errors", &[
+        ("main.nl", "#one@dep.nl\n#main (vec<str> args) int << one();\n"),
+        ("dep.nl", "<< #one () int << \"one\";\n"),
+    ]);
+    let config = Config::new(&directory.join("main.nl").to_string_lossy());
+    let error = Loader::load(&config).err().unwrap();
+    assert!(error.path.ends_with("dep.nl"));
+    assert_eq!(error.diagnostics[0].message, "Mismatched types, expected `int`, found `str`");
+}
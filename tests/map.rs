@@ -0,0 +1,84 @@
+mod common;
+
+use common::{errors, run};
+use nebulang::Engine;
+
+#[test]
+fn maps_are_read_and_rebuilt() {
+    let (code, output) = run("maps", r#"^map@std;
+#main (vec<str> args) int {
+    map<str><int> ages = ["ann", 30, "bob", 41];
+    map<str><int> before = ages;
+    ages = ages.set("cid", 5).remove("bob");
+    print("{} {} {} {}", ages.ann, ages["cid"], ages.has("bob"), ages.keys().join(","));
+    map<int><bol> seen = [1, true];
+    print("{} {}", seen[1], before.has("bob"));
+    << ages.get("ann")
+}
+"#);
+    assert_eq!((code, output.as_str()), (30, "30 5 false ann,cid\ntrue true\n"));
+}
+
+#[test]
+fn maps_come_from_std() {
+    let found = errors("no_std", r#"#main (vec<str> args) int {
+    map<str><int> a = ["x", 1];
+    << 0
+}
+"#);
+    assert_eq!(found[0], "Cannot find type `map`");
+    let found = errors("std_list", "^list@std;\n#main (vec<str> args) int << 0;\n");
+    assert_eq!(found, vec!["Cannot find `list` in the std library"]);
+}
+
+#[test]
+fn keys_and_values_are_checked() {
+    let found = errors("map_types", r#"^map@std;
+#main (vec<str> args) int {
+    map<str><int> a = ["x", "y"];
+    int d = a.get(1);
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Mismatched types, expected `int`, found `str`",
+        "Mismatched types, expected `str`, found `int`",
+    ]);
+    let found = errors("map_params", r#"^map@std;
+#main (vec<str> args) int {
+    map<vec<int>><int> b = [[1], 2];
+    map<str> c = ["x", "y"];
+    << 0
+}
+"#);
+    assert!(found.contains(&"Maps cannot have keys of type `vec<int>`".to_string()), "{:?}", found);
+    assert!(found.contains(&"`map` expects 2 type parameters, found 1".to_string()), "{:?}", found);
+}
+
+#[test]
+fn map_types_are_shown_as_written() {
+    let found = errors("map_written", r#"^map@std;
+#main (vec<str> args) int {
+    map<int><int> a = [1, 2];
+    int b = a.name;
+    str c = a;
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "`map<int><int>` has no field `name`",
+        "Mismatched types, expected `str`, found `map<int><int>`",
+    ]);
+}
+
+#[test]
+fn missing_keys_stop_the_program() {
+    let mut engine = Engine::new();
+    engine.load_source("keys.nl", r#"^map@std;
+#main (vec<str> args) int {
+    map<str><int> a = ["x", 1];
+    << a.y
+}
+"#).unwrap();
+    assert_eq!(engine.run(vec![]).unwrap_err().to_string(), "Map has no key `y` in #main at 4:10");
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::{errors, run};
+
+const PERSON: &str = r#"^person {
+    int id;
+    str name;
+}
+#new^person (int id, str name) self << { id: id, name: name };
+#nameplate^person (self self) str << format("{}:{}", self.id, self.name);
+#renamed^person (self self, str name) person << person:new(self.id, name);
+"#;
+
+#[test]
+fn methods_take_the_value_they_are_called_on() {
+    let source = format!("{}{}", PERSON, r#"#main (vec<str> args) int {
+    person p = person:new(69, "John");
+    print(p.nameplate());
+    print(p.renamed("Jane").nameplate());
+    print(person:nameplate(p));
+    << p.renamed("x").id
+}
+"#);
+    let (code, output) = run("methods", &source);
+    assert_eq!((code, output.as_str()), (69, "69:John\n69:Jane\n69:John\n"));
+}
+
+#[test]
+fn methods_and_functions_of_structs_are_checked() {
+    let source = format!("{}{}", PERSON, r#"#main (vec<str> args) int {
+    person p = person:new(1, "a");
+    str a = p.missing();
+    person q = p.new(2, "b");
+    str d = person:nameplate();
+    int e = p.nameplate();
+    str f = p.nameplate(1);
+    str g = nobody:new();
+    << 0
+}
+"#);
+    assert_eq!(errors("method_errors", &source), vec![
+        "`person` has no method `missing`",
+        "`person` has no method `new`",
+        "`person:nameplate` expects 1 arguments, found 0",
+        "Mismatched types, expected `int`, found `str`",
+        "`nameplate` expects 0 arguments, found 1",
+        "`nobody` has no function `new`",
+    ]);
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::errors;
+
+#[test]
+fn numbers_change_in_place() {
+    let found = errors("numbers", r#"#main (vec<str> args) int {
+    int a = 1;
+    flt b = 1.5;
+    a += 2;
+    a -= 1;
+    a++;
+    b += 0.5;
+    b--;
+    a = a * 2;
+    << a
+}
+"#);
+    assert!(found.is_empty(), "{:?}", found);
+}
+
+#[test]
+fn other_types_are_reassigned() {
+    let found = errors("reassigned", r#"#main (vec<str> args) int {
+    str s = "a";
+    s = s + "b";
+    s += "c";
+    vec<int> list = [1];
+    list++;
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Cannot use `+=` on a value of type `str`",
+        "Cannot use `++` on a value of type `vec<int>`",
+    ]);
+}
+
+#[test]
+fn reassignment_needs_a_declaration() {
+    let found = errors("undeclared", r#"#main (vec<str> args) int {
+    a = 1;
+    b += 1;
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Cannot find `a` in this scope",
+        "Cannot find `b` in this scope",
+    ]);
+}
+
+#[test]
+fn vecs_and_maps_are_rebound() {
+    let found = errors("rebound", r#"^map@std;
+#main (vec<str> args) int {
+    vec<int> list = [1];
+    list = list.push(2);
+    list.push(3);
+    map<str><int> ages = ["a", 1];
+    ages.set("b", 2);
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "The result of `push` is not used",
+        "The result of `set` is not used",
+    ]);
+}
+
+#[test]
+fn fields_and_items_are_not_assigned() {
+    let found = errors("fields", r#"^person {
+    str name;
+}
+#main (vec<str> args) int {
+    person p = { name: "a" };
+    p.name = "b";
+    vec<int> list = [1];
+    list[0] = 2;
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Fields cannot be assigned, structs cannot be changed in place",
+        "Items cannot be assigned, vecs and maps cannot be changed in place",
+    ]);
+}
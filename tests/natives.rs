@@ -0,0 +1,65 @@
+use nebulang::engine::{
+    compiler::Compiler,
+    config::Config,
+    core::{register, Native},
+    parser::Parser,
+    types::{DataTypes, TypeSignature},
+    vm::{Value, Vm},
+};
+
+/// Parses the source from memory with the runtime natives and `double`,
+/// which takes an int
+fn parse(name: &str, source: &str) -> Result<Parser, Vec<String>> {
+    let mut parser = Parser::new(Config::new(&format!("{}.nl", name)));
+    parser.natives.register("double", double()).unwrap();
+    parser.parse_source(source)
+        .map(|_| parser)
+        .map_err(|diagnostics| diagnostics.into_iter().map(|diagnostic| diagnostic.message).collect())
+}
+
+fn double() -> Native {
+    let int = TypeSignature::new(DataTypes::Int);
+    Native::new(vec![int.clone()], int, |args| match args {
+        [Value::Int(int)] => Ok(Value::Int(int * 2)),
+        _ => Err("#double takes an int".to_string()),
+    })
+}
+
+#[test]
+fn registered_natives_are_called() {
+    let parser = parse("called", r#"#main (vec<str> args) int {
+    int a = double(20);
+    << a + double(1)
+}
+"#).unwrap();
+    let program = Compiler::emit(&parser).unwrap();
+    let mut vm = Vm::with_natives(program, parser.natives.clone());
+    assert_eq!(vm.run(vec![]).unwrap(), 42);
+}
+
+#[test]
+fn arguments_are_checked_against_the_registry() {
+    let found = parse("arguments", r#"#main (vec<str> args) int {
+    int a = double("a");
+    int b = double(1, 2);
+    str c = double(1);
+    vec<int> list = [1];
+    list = list.push("b");
+    << 0
+}
+"#).unwrap_err();
+    assert_eq!(found, vec![
+        "Mismatched types, expected `int`, found `str`",
+        "`double` expects 1 arguments, found 2",
+        "Mismatched types, expected `str`, found `int`",
+        "Mismatched types, expected `int`, found `str`",
+    ]);
+}
+
+#[test]
+fn names_are_registered_once() {
+    let mut natives = register();
+    assert!(natives.register("print", double()).is_err());
+    assert!(natives.register("triple", double()).is_ok());
+    assert!(natives.contains("triple"));
+}
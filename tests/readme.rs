@@ -0,0 +1,51 @@
+mod common;
+
+use common::{files, run};
+use nebulang::Engine;
+
+/// The `nebulang` code blocks of the README, in order
+fn examples() -> Vec<&'static str> {
+    include_str!("../README.md")
+        .split("```nebulang\n")
+        .skip(1)
+        .map(|block| block.split("```").next().unwrap())
+        .collect()
+}
+
+#[test]
+fn readme_examples_run() {
+    let examples = examples();
+    let text = "The quick brown fox jumps over the lazy dog";
+    let characters: String = text.chars().map(|character| format!("{}\n", character)).collect();
+    let counted: String = (0..10).map(|index| format!("{}\n", index)).collect();
+    let expected = [
+        "3\n",
+        "Values are equal!\n",
+        &counted,
+        "",
+        &characters,
+        "6\n",
+        "2\n",
+        "Hello World John\n",
+        "Nebulang scripted\n",
+        "Hello \"World\"\n",
+        "Personal ID: 69\nNameplate: 69:John\n",
+        "pet crab,take vitamins\n",
+        "Hello World\n",
+    ];
+    assert_eq!(examples.len(), expected.len());
+    for (index, (example, expected)) in examples.iter().zip(expected.iter()).enumerate() {
+        // The import example holds both of its files
+        let output = match example.split_once("// dep.nl\n") {
+            Some((main, dep)) => {
+                let directory = files("readme", &[("main.nl", main), ("dep.nl", dep)]);
+                let mut engine = Engine::new();
+                engine.load_file(&directory.join("main.nl").to_string_lossy()).unwrap();
+                engine.run(vec![]).unwrap();
+                engine.take_output()
+            }
+            None => run(&format!("readme{}", index), example).1,
+        };
+        assert_eq!(output, *expected, "example {} of the README", index);
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::{errors, run};
+
+#[test]
+fn inner_blocks_shadow_and_end_their_variables() {
+    let (_, output) = run("shadowing", r#"#main (vec<str> args) int {
+    int value = 1;
+    ? value > 0 {
+        str value = "inner";
+        print(value);
+        ! {
+            int value = 3;
+            print("{}", value);
+            << !
+        }
+        print(value);
+    }
+    ? value > 0 {
+        int value = 2;
+        print("{}", value);
+    }
+    print("{}", value);
+    << 0
+}
+"#);
+    assert_eq!(output, "inner\n3\ninner\n2\n1\n");
+}
+
+#[test]
+fn variables_are_declared_once_before_use() {
+    let found = errors("declared", r#"#main (vec<str> args) int {
+    print("{}", later);
+    int later = 1;
+    int later = 2;
+    ? later > 0 {
+        int inner = 3;
+    }
+    print("{}", inner);
+    << later
+}
+"#);
+    assert_eq!(found, vec![
+        "Cannot find `later` in this scope",
+        "`later` is already declared in this block",
+        "Cannot find `inner` in this scope",
+    ]);
+}
+
+#[test]
+fn functions_do_not_see_each_others_variables() {
+    let found = errors("separate", r#"#one () int {
+    int shared = 1;
+    << shared
+}
+#two () int << shared;
+"#);
+    assert_eq!(found, vec!["Cannot find `shared` in this scope"]);
+}
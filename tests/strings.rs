@@ -0,0 +1,218 @@
+use nebulang::engine::{
+    config::extract_config,
+    diagnostic::Diagnostic,
+    parser::Parser,
+    types::TokenTypes,
+};
+
+/// Parses the source from memory with the flags given
+fn parse(name: &str, source: &str, flags: &[&str]) -> Result<Parser, Vec<Diagnostic>> {
+    let mut args = vec![
+        "nebulang".to_string(),
+        "check".to_string(),
+        format!("{}.nl", name),
+    ];
+    args.extend(flags.iter().map(|flag| flag.to_string()));
+    let config = extract_config(&args).expect("Could not read the flags");
+    let mut parser = Parser::new(config);
+    parser.parse_source(source).map(|_| parser)
+}
+
+fn strings(parser: &Parser) -> Vec<String> {
+    parser.nodes
+        .iter()
+        .filter_map(|node| match &node.token {
+            TokenTypes::LiteralString(string) => Some(string.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn variables(parser: &Parser) -> Vec<String> {
+    parser.nodes
+        .iter()
+        .filter_map(|node| match &node.token {
+            TokenTypes::Variable(variable) => Some(variable.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn double_and_single_quotes() {
+    let parser = parse("quotes", r#"#main (vec<str> args) int {
+    str a = "it's";
+    str b = 'double "quoting"';
+    << 0
+}
+"#, &[]).unwrap();
+    assert_eq!(strings(&parser), vec!["it's", "double \"quoting\""]);
+}
+
+#[test]
+fn global_delimiter_from_config() {
+    let parser = parse("global", r#"#main (vec<str> args) int {
+    str a = %it's "all" fine%;
+    str b = "still";
+    str c = 'there';
+    << 0
+}
+"#, &["--qq", "%"]).unwrap();
+    assert_eq!(strings(&parser), vec!["it's \"all\" fine", "still", "there"]);
+}
+
+#[test]
+fn global_delimiter_is_not_a_delimiter_without_the_flag() {
+    let result = parse("no_global", r#"#main (vec<str> args) int {
+    str a = %text%;
+    << 0
+}
+"#, &[]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn temporary_delimiter() {
+    let parser = parse("temporary", r#"#main (vec<str> args) int {
+    str a = qq*Hello "World"*;
+    str b = qq|it's|;
+    str c = "after";
+    << 0
+}
+"#, &[]).unwrap();
+    assert_eq!(strings(&parser), vec!["Hello \"World\"", "it's", "after"]);
+}
+
+#[test]
+fn identifiers_with_q() {
+    let parser = parse("identifiers", r#"#main (vec<str> args) int {
+    int quiet = 1;
+    int square = quiet * 2;
+    int qq = square + 1;
+    int aqqb = qq;
+    << aqqb
+}
+"#, &[]).unwrap();
+    assert!(strings(&parser).is_empty());
+    let variables = variables(&parser);
+    for name in ["quiet", "square", "qq", "aqqb"] {
+        assert!(variables.iter().any(|variable| variable == name), "missing {}", name);
+    }
+}
+
+#[test]
+fn long_strings_are_kept_whole() {
+    let long = "a".repeat(200);
+    let source = format!("#main (vec<str> args) int {{\n    str a = \"{}\";\n    << 0\n}}\n", long);
+    let parser = parse("long", &source, &[]).unwrap();
+    assert_eq!(strings(&parser), vec![long]);
+}
+
+#[test]
+fn unterminated_string() {
+    let diagnostics = parse("unterminated", r#"#main (vec<str> args) int {
+    str a = 'open;
+    << 0
+}
+"#, &[]).unwrap_err();
+    assert_eq!(diagnostics[0].message, "Unterminated string");
+    assert_eq!(diagnostics[0].start.line, 2);
+}
+
+#[test]
+fn escapes_are_decoded() {
+    let parser = parse("escapes", r#"#main (vec<str> args) int {
+    str a = "tab\there\n\"quoted\" back\\slash";
+    str b = 'it\'s \u{1F30C}';
+    str c = qq*star \* inside*;
+    << 0
+}
+"#, &[]).unwrap();
+    assert_eq!(strings(&parser), vec![
+        "tab\there\n\"quoted\" back\\slash",
+        "it's \u{1F30C}",
+        "star * inside",
+    ]);
+}
+
+#[test]
+fn unknown_escapes_point_at_their_column() {
+    let diagnostics = parse("unknown_escape", r#"#main (vec<str> args) int {
+    str a = "bad \q escape";
+    str b = "line one
+and \u{110000}";
+    << 0
+}
+"#, &[]).unwrap_err();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].message, "Unknown escape `\\q`");
+    assert_eq!((diagnostics[0].start.line, diagnostics[0].start.column), (2, 18));
+    assert_eq!((diagnostics[0].end.line, diagnostics[0].end.column), (2, 19));
+    assert_eq!(diagnostics[1].message, "`110000` is not a unicode code point");
+    assert_eq!((diagnostics[1].start.line, diagnostics[1].start.column), (4, 5));
+}
+
+fn chars(parser: &Parser) -> Vec<char> {
+    parser.nodes
+        .iter()
+        .filter_map(|node| match &node.token {
+            TokenTypes::LiteralChar(chr) => Some(*chr),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn char_literals() {
+    let parser = parse("chars", r#"#main (vec<str> args) int {
+    chr a = `a`;
+    chr b = `\n`;
+    chr c = `\``;
+    chr d = `"`;
+    str e = "`";
+    << 0
+}
+"#, &[]).unwrap();
+    assert_eq!(chars(&parser), vec!['a', '\n', '`', '"']);
+    assert_eq!(strings(&parser), vec!["`"]);
+}
+
+#[test]
+fn char_literals_hold_one_character() {
+    let diagnostics = parse("long_char", r#"#main (vec<str> args) int {
+    chr a = `ab`;
+    << 0
+}
+"#, &[]).unwrap_err();
+    assert_eq!(diagnostics[0].message, "Char literals hold one character, found 2");
+    assert_eq!((diagnostics[0].start.line, diagnostics[0].start.column), (2, 13));
+}
+
+#[test]
+fn sources_are_named_in_symbols_and_diagnostics() {
+    let parser = parse("named", r#"#main (vec<str> args) int {
+    << 0
+}
+"#, &[]).unwrap();
+    assert!(parser.symbols.iter().all(|symbol| &*symbol.source == "named.nl"));
+
+    let diagnostics = parse("unnamed_variable", r#"#main (vec<str> args) int {
+    << missing
+}
+"#, &[]).unwrap_err();
+    assert_eq!(&*diagnostics[0].source, "unnamed_variable.nl");
+    assert!(diagnostics[0].render("").contains("--> unnamed_variable.nl:2:8"));
+}
+
+#[test]
+fn any_reader_is_a_source() {
+    let source = "#main (vec<str> args) int {\n    str a = \"ünïcødé 🌌\";\n    << 0\n}\n";
+    for flags in [&[][..], &["--low-mem"][..]] {
+        let mut args = vec!["nebulang", "check", "reader.nl"];
+        args.extend(flags);
+        let args: Vec<String> = args.into_iter().map(String::from).collect();
+        let mut parser = Parser::new(extract_config(&args).unwrap());
+        parser.parse(source.as_bytes()).unwrap();
+        assert_eq!(strings(&parser), vec!["ünïcødé 🌌"]);
+    }
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{errors, run};
+
+#[test]
+fn structs_hold_their_fields() {
+    let (_, output) = run("structs", r#"^point {
+    int x;
+    int y;
+}
+^line {
+    point from;
+    point to;
+    str name;
+}
+#length (line l) int << l.to.x - l.from.x + l.to.y - l.from.y;
+#main (vec<str> args) int {
+    point start = { y: 2, x: 1 };
+    line l = {
+        from: start,
+        to: { x: 4, y: 6 },
+        name: "diagonal"
+    };
+    print("{} {} {} {}", l.name, l.from.y, l.to.x, length(l));
+    print("{}", start);
+    << 0
+}
+"#);
+    assert_eq!(output, "diagonal 2 4 7\n{1, 2}\n");
+}
+
+#[test]
+fn struct_literals_match_their_declaration() {
+    let found = errors("struct_errors", r#"^point {
+    int x;
+    int y;
+}
+^point {
+    int z;
+}
+^line {
+    point from;
+    nope extra;
+}
+#main (vec<str> args) int {
+    point a = { x: 1 };
+    point b = { x: 1, y: 2, z: 3 };
+    point c = { x: "1", y: 2 };
+    int d = a.w;
+    point e;
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Struct `point` is already defined",
+        "Cannot find type `nope`",
+        "Missing fields `y` in `point`",
+        "`point` has no field `z`",
+        "Mismatched types, expected `int`, found `str`",
+        "`point` has no field `w`",
+        "Variables of type `point` have to be given a value",
+    ]);
+}
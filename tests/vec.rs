@@ -0,0 +1,44 @@
+mod common;
+
+use common::{errors, run};
+use nebulang::Engine;
+
+#[test]
+fn vecs_are_written_indexed_and_rebuilt() {
+    let (_, output) = run("vecs", r#"#main (vec<str> args) int {
+    vec<int> a = [1, 2, 3];
+    vec<vec<str>> nested = [["a"], ["b", "c"]];
+    vec<int> before = a;
+    a = a.push(4).unshift();
+    print("{} {} {} {}", a.length, a[0], nested[1][1], a.join(","));
+    vec<int> empty = []~vec<int>;
+    print("{} {} {}", empty.length, before.length, ["x", "y"].join(""));
+    << 0
+}
+"#);
+    assert_eq!(output, "3 2 c 2,3,4\n0 3 xy\n");
+}
+
+#[test]
+fn vec_items_have_one_type() {
+    let found = errors("vec_types", r#"#main (vec<str> args) int {
+    vec<int> a = [1, "2"];
+    int b = a["0"];
+    str c = a[0];
+    << 0
+}
+"#);
+    assert_eq!(found, vec![
+        "Mismatched types, expected `int`, found `str`",
+        "Mismatched types, expected `int`, found `str`",
+        "Mismatched types, expected `str`, found `int`",
+    ]);
+}
+
+#[test]
+fn indexes_past_the_end_stop_the_program() {
+    let mut engine = Engine::new();
+    engine.load_source("bounds.nl", "#main (vec<str> args) int {\n    vec<int> a = [1];\n    << a[3]\n}\n").unwrap();
+    let error = engine.run(vec![]).unwrap_err();
+    assert_eq!(error.to_string(), "Index 3 is out of bounds for a vec of 1 items in #main at 3:9");
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::{errors, run};
+use nebulang::engine::{
+    bytecode::{Function, OpCodes, Program},
+    types::Cursor,
+    vm::{Value, Vm},
+};
+use nebulang::Engine;
+
+/// A program of a single function running the code
+fn program(params: u8, locals: u32, code: Vec<OpCodes>) -> Program {
+    let mut function = Function::new("main".to_string(), params);
+    function.locals = locals;
+    for op in code {
+        function.emit(op, Cursor::default());
+    }
+    Program {
+        functions: vec![function],
+        entry: Some(0),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn broken_bytecode_is_an_error() {
+    let broken = [
+        program(0, 1, vec![OpCodes::Load(1), OpCodes::Return]),
+        program(0, 0, vec![OpCodes::PushInt(1), OpCodes::Store(0), OpCodes::Return]),
+        program(0, 0, vec![OpCodes::Call(0, 0), OpCodes::Call(3, 0)]),
+        program(0, 0, vec![OpCodes::Pop]),
+        program(0, 0, vec![OpCodes::Jump(7)]),
+    ];
+    for program in broken {
+        assert!(Vm::new(program).run(vec![]).is_err());
+    }
+    let mut two = program(2, 2, vec![OpCodes::Load(0), OpCodes::Return]);
+    two.functions.push(Function::new("one".to_string(), 1));
+    let error = Vm::new(two.clone()).call(0, vec![Value::Int(1)]).unwrap_err();
+    assert_eq!(error.to_string(), "Function expects 2 arguments, got 1 in #main");
+    let mut calls = two;
+    calls.functions[1].emit(OpCodes::Call(0, 2), Cursor::default());
+    assert!(Vm::new(calls).call(1, vec![Value::Int(1)]).is_err());
+}
+
+#[test]
+fn main_takes_the_arguments_and_returns_the_code() {
+    let found = errors("main", r#"#main (vec<str> args, int count) int << 0;
+"#);
+    assert_eq!(found, vec!["`main` has to take a `vec<str>` or nothing and return an `int` or nothing"]);
+    let found = errors("main", r#"#main () str << "done";
+"#);
+    assert_eq!(found, vec!["`main` has to take a `vec<str>` or nothing and return an `int` or nothing"]);
+    assert!(errors("main", "#main () { print(1); }\n").is_empty());
+}
+
+#[test]
+fn programs_run_to_their_exit_code() {
+    let (code, output) = run("fib", r#"#fib (int n) int {
+    ? n < 2 {
+        << n
+    }
+    << fib(n - 1) + fib(n - 2)
+}
+#main (vec<str> args) int {
+    print("{} {}", fib(15), args.length);
+    << fib(6)
+}
+"#);
+    assert_eq!((code, output.as_str()), (8, "610 0\n"));
+}
+
+#[test]
+fn runtime_errors_name_where_they_happened() {
+    let mut engine = Engine::new();
+    engine.load_source("fail.nl", r#"#divide (int a, int b) int {
+    << a / b
+}
+#forever (int n) int << forever(n + 1);
+#main (vec<str> args) int << divide(1, 0);
+"#).unwrap();
+    let error = engine.run(vec![]).unwrap_err();
+    assert_eq!(error.to_string(), "Division by zero in #divide at 2:10");
+    let error = engine.call("forever", vec![Value::Int(0)]).unwrap_err();
+    assert!(error.to_string().starts_with("Stack overflow in #forever"));
+}
+
+#[test]
+fn mismatched_operands_name_the_operator() {
+    let strings = [OpCodes::PushStr(0), OpCodes::PushInt(1)];
+    for (op, operator) in [(OpCodes::Add, "+"), (OpCodes::BitLeft, "<<")] {
+        let mut program = program(0, 0, strings.iter().cloned().chain([op, OpCodes::Return]).collect());
+        program.strings = vec!["a".to_string()];
+        let error = Vm::new(program).run(vec![]).unwrap_err();
+        assert_eq!(error.to_string(), format!("Cannot apply `{}` to str and int in #main at 1:1", operator));
+    }
+}